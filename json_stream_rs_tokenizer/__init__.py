@@ -8,17 +8,26 @@ __all__ = [
 ]
 
 
+_DEFAULT_ASYNC_CHUNK_SIZE = 8000
+
+
 class TokenType:
     Operator = 0
     String_ = 1
     Number = 2
     Boolean = 3
     Null = 4
+    Error_ = 5
+    RecordEnd = 6
+    ValueStart = 7
 
 
 try:
     from .json_stream_rs_tokenizer import (
+        ContinuationStream,
+        Diagnostic,
         RustTokenizer as _RustTokenizer,
+        TruncatedValueWarning,
         supports_bigint as _supports_bigint,
     )
 
@@ -39,26 +48,148 @@ try:
             def __init__(self, *args, **kwargs):
                 self.inner = _RustTokenizer(*args, **kwargs)
 
+            @classmethod
+            def incremental(cls, **kwargs):
+                self = cls.__new__(cls)
+                self.inner = _RustTokenizer.incremental(**kwargs)
+                return self
+
+            @classmethod
+            def from_str(cls, s, **kwargs):
+                self = cls.__new__(cls)
+                self.inner = _RustTokenizer.from_str(s, **kwargs)
+                return self
+
+            @classmethod
+            def from_path(cls, path, **kwargs):
+                self = cls.__new__(cls)
+                self.inner = _RustTokenizer.from_path(path, **kwargs)
+                return self
+
             def __iter__(self):
                 return self
 
             def __next__(self):
-                # x = (token_type, value) but {un&re}packing worsens perf
-                x = self.inner.__next__()
-                if x[0] == TokenType.Number and isinstance(x[1], str):
-                    # fallback required for large integers
-                    return (x[0], int(x[1]))
-                else:
-                    return x
+                return _convert_large_int(self.inner.__next__())
 
             @property
             def remainder(self):
                 return self.inner.remainder
 
+            @property
+            def errors(self):
+                return self.inner.errors
+
+            @property
+            def partial(self):
+                return self.inner.partial
+
+            @property
+            def compressed_offset(self):
+                return self.inner.compressed_offset
+
+            @property
+            def decompressed_offset(self):
+                return self.inner.decompressed_offset
+
+            def open_containers(self):
+                return self.inner.open_containers()
+
             def park_cursor(self):
                 self.inner.park_cursor()
 
-    __all__.extend(["RustTokenizer", "supports_bigint"])
+            def into_stream(self):
+                return self.inner.into_stream()
+
+            def feed(self, data):
+                self.inner.feed(data)
+
+            def close(self):
+                self.inner.close()
+
+            def reset(self):
+                self.inner.reset()
+
+            def documents(self):
+                for document in self.inner.documents():
+                    yield map(_convert_large_int, document)
+
+        def _convert_large_int(x):
+            # x = (token_type, value) but {un&re}packing worsens perf
+            if x[0] == TokenType.Number and isinstance(x[1], str):
+                # fallback required for large integers
+                return (x[0], int(x[1]))
+            else:
+                return x
+
+    class AsyncRustTokenizer:
+        """
+        Asynchronous variant of `RustTokenizer`.
+
+        Reads from an `asyncio.StreamReader` or any other object with an
+        awaitable `read(n)` method and is iterated over via `async for`.
+
+        Args:
+            stream: Stream to read JSON from. Its `read(n)` method may return
+                either str or bytes (so long as the bytes are valid UTF-8).
+            buffering: Number of chars or bytes to read at once. -1 (the
+                default) means to let the implementation choose a buffer
+                size, 0 or 1 means to read one char or byte at a time.
+            kwargs: Keyword arguments to pass on to
+                `RustTokenizer.incremental()`.
+        """
+
+        def __init__(self, stream, *, buffering=-1, **kwargs):
+            self.stream = stream
+            if buffering < 0:
+                self.chunk_size = _DEFAULT_ASYNC_CHUNK_SIZE
+            else:
+                self.chunk_size = max(buffering, 1)
+            self.tokenizer = RustTokenizer.incremental(**kwargs)
+            self.eof = False
+
+        def __aiter__(self):
+            return self
+
+        async def __anext__(self):
+            while True:
+                token = next(self.tokenizer, None)
+                if token is not None:
+                    return token
+                if self.eof:
+                    raise StopAsyncIteration
+                data = await self.stream.read(self.chunk_size)
+                if data:
+                    self.tokenizer.feed(data)
+                else:
+                    self.tokenizer.close()
+                    self.eof = True
+
+        @property
+        def remainder(self):
+            return self.tokenizer.remainder
+
+        @property
+        def errors(self):
+            return self.tokenizer.errors
+
+        @property
+        def partial(self):
+            return self.tokenizer.partial
+
+        def open_containers(self):
+            return self.tokenizer.open_containers()
+
+    __all__.extend(
+        [
+            "AsyncRustTokenizer",
+            "ContinuationStream",
+            "Diagnostic",
+            "RustTokenizer",
+            "TruncatedValueWarning",
+            "supports_bigint",
+        ]
+    )
 except ImportError:
     pass
 
@@ -90,7 +221,22 @@ def rust_tokenizer_or_raise(requires_bigint=True, **kwargs):
         ExtensionUnavailable: If the Rust extension is not available.
         RequestedFeatureUnavailable: If a requested feature is not available.
     """
-    supported_kwargs = {"buffering"}
+    supported_kwargs = {
+        "buffering",
+        "ndjson",
+        "json_seq",
+        "recover",
+        "emit_error_tokens",
+        "scan",
+        "allow_partial",
+        "follow",
+        "poll_interval",
+        "follow_timeout",
+        "compression",
+        "mode",
+        "seekable",
+        "prefetch",
+    }
     unsupported = kwargs.keys() - supported_kwargs
     if unsupported:
         raise RequestedFeatureUnavailable(
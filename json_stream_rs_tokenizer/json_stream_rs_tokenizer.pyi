@@ -3,24 +3,141 @@ Manually written type hints stub file until PyO3 supports stub generation.
 
 See https://pyo3.rs/v0.27.1/python-typing-hints.html
 """
-from typing import Any, IO, final
+from os import PathLike
+from typing import Any, IO, Iterable, Iterator, Literal, final
+from typing_extensions import Buffer
 
 @final
 class RustTokenizer:
   # TODO: buffering default is actually -1 but Mypy insists on it being
   #       ellipsis...
   def __new__(
-    cls, stream: IO[Any], *, buffering: int = ..., correct_cursor: bool = False
+    cls,
+    stream: IO[Any] | str | Buffer | Iterable[str] | Iterable[bytes],
+    *,
+    buffering: int | Literal["adaptive"] = ...,
+    correct_cursor: bool = False,
+    ndjson: bool = False,
+    json_seq: bool = False,
+    recover: bool = False,
+    emit_error_tokens: bool = False,
+    scan: bool = False,
+    allow_partial: bool = False,
+    follow: bool = False,
+    poll_interval: float = 0.1,
+    follow_timeout: float | None = None,
+    compression: (
+      Literal["auto", "gzip", "zstd", "bz2", "xz"] | None
+    ) = None,
+    mode: Literal["bytes", "text"] | None = None,
+    seekable: bool | None = None,
+    prefetch: bool = False,
   ) -> RustTokenizer: ...
 
+  @classmethod
+  def incremental(
+    cls,
+    *,
+    ndjson: bool = False,
+    json_seq: bool = False,
+    recover: bool = False,
+    emit_error_tokens: bool = False,
+    scan: bool = False,
+    allow_partial: bool = False,
+  ) -> RustTokenizer: ...
+
+  @classmethod
+  def from_str(
+    cls,
+    s: str,
+    *,
+    ndjson: bool = False,
+    json_seq: bool = False,
+    recover: bool = False,
+    emit_error_tokens: bool = False,
+    scan: bool = False,
+    allow_partial: bool = False,
+  ) -> RustTokenizer: ...
+
+  @classmethod
+  def from_path(
+    cls,
+    path: str | PathLike[str],
+    *,
+    ndjson: bool = False,
+    json_seq: bool = False,
+    recover: bool = False,
+    emit_error_tokens: bool = False,
+    scan: bool = False,
+    allow_partial: bool = False,
+  ) -> RustTokenizer: ...
+
+  def feed(self, data: str | bytes) -> None: ...
+
+  def close(self) -> None: ...
+
   def park_cursor(self) -> None: ...
 
   @property
   def remainder(self) -> str | bytes: ...
 
+  def into_stream(self) -> ContinuationStream: ...
+
+  def reset(self) -> None: ...
+
+  @property
+  def errors(self) -> list[Diagnostic]: ...
+
+  @property
+  def partial(self) -> bool: ...
+
+  @property
+  def compressed_offset(self) -> int | None: ...
+
+  @property
+  def decompressed_offset(self) -> int | None: ...
+
+  @property
+  def file_offset(self) -> int | None: ...
+
+  def open_containers(self) -> list[str]: ...
+
+  def documents(self) -> Iterator[Iterator[tuple[int, Any]]]: ...
+
+@final
+class Diagnostic:
+  @property
+  def message(self) -> str: ...
+  @property
+  def index(self) -> int: ...
+  @property
+  def record(self) -> int | None: ...
+
+@final
+class ContinuationStream:
+  def read(self, size: int | None = -1, /) -> Any: ...
+  def readable(self) -> bool: ...
+  def seekable(self) -> bool: ...
+  def writable(self) -> bool: ...
+  def close(self) -> None: ...
+  @property
+  def closed(self) -> bool: ...
+  def __enter__(self) -> ContinuationStream: ...
+  def __exit__(
+    self,
+    _exc_type: object,
+    _exc_value: object,
+    _traceback: object,
+  ) -> None: ...
+
+class TruncatedValueWarning(UserWarning): ...
+
 def supports_bigint() -> bool: ...
 
 __all__ = [
+  "ContinuationStream",
+  "Diagnostic",
   "RustTokenizer",
+  "TruncatedValueWarning",
   "supports_bigint",
 ]
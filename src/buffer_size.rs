@@ -0,0 +1,80 @@
+/// Buffer sizes for the buffered stream adapters, either fixed or adaptive.
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Initial buffer size for adaptive buffering, small to get the first tokens out quickly.
+const ADAPTIVE_INITIAL_BUFSIZE: usize = 256;
+/// Buffer size beyond which adaptive buffering doesn't grow the buffer any further.
+const ADAPTIVE_MAX_BUFSIZE: usize = 65536;
+
+/// Size of the chunks in which a stream is read.
+///
+/// Adaptive sizes start out small, which keeps the latency until the first token low for
+/// interactive streams, and double with each read up to a cap, so large files are still read in
+/// large chunks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BufferSize {
+    current: usize,
+    max: usize,
+}
+
+impl BufferSize {
+    pub fn fixed(size: usize) -> Self {
+        BufferSize {
+            current: size,
+            max: size,
+        }
+    }
+
+    pub fn adaptive() -> Self {
+        BufferSize {
+            current: ADAPTIVE_INITIAL_BUFSIZE,
+            max: ADAPTIVE_MAX_BUFSIZE,
+        }
+    }
+
+    /// Largest size this will ever return.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Size to use for the next read, growing it for the one after that.
+    pub fn next_size(&mut self) -> usize {
+        let size = self.current;
+        self.current = size.saturating_mul(2).min(self.max);
+        size
+    }
+}
+
+/// Reader that limits each read to the next size given by a `BufferSize`.
+///
+/// This is how adaptive buffering is implemented for `utf8_read::Reader`, whose chunk size is
+/// fixed: its chunk size is set to the maximum and this limits how much of it is filled by each
+/// read.
+pub struct SizeLimitedReader<R> {
+    inner: R,
+    buffer_size: BufferSize,
+}
+
+impl<R> SizeLimitedReader<R> {
+    pub fn new(inner: R, buffer_size: BufferSize) -> Self {
+        SizeLimitedReader { inner, buffer_size }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.buffer_size.max()
+    }
+}
+
+impl<R: Read> Read for SizeLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.buffer_size.next_size().min(buf.len());
+        self.inner.read(&mut buf[..size])
+    }
+}
+
+impl<R: Seek> Seek for SizeLimitedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
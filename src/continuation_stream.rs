@@ -0,0 +1,166 @@
+/// File-like object for continuing to read where a tokenizer left off.
+use crate::feed_stream::FeedData;
+use crate::remainder::StreamData;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+/// Readable file-like object returned by `RustTokenizer.into_stream()`.
+///
+/// Reading from it first returns the data that the tokenizer had read from
+/// its source but not processed and then the data that follows it in the
+/// source. Closing it doesn't close the source.
+#[pyclass]
+pub struct ContinuationStream {
+    buffer: StreamData,        // data to return before reading from the source
+    source: Option<Py<PyAny>>, // file-like object or iterator over chunks
+    closed: bool,
+}
+
+impl ContinuationStream {
+    pub fn new(buffer: StreamData, source: Option<Py<PyAny>>) -> Self {
+        ContinuationStream {
+            buffer,
+            source,
+            closed: false,
+        }
+    }
+
+    /// Length of the buffered data in chars (text) or bytes (binary data).
+    fn buffered_len(&self) -> usize {
+        match &self.buffer {
+            StreamData::Text(s) => s.chars().count(),
+            StreamData::Bytes(b) => b.len(),
+        }
+    }
+
+    /// Take up to `n` chars or bytes (all if `None`) from the start of the buffered data.
+    fn take_buffered(&mut self, n: Option<usize>) -> StreamData {
+        match &mut self.buffer {
+            StreamData::Text(s) => {
+                let split_at = n
+                    .and_then(|n| s.char_indices().nth(n))
+                    .map_or(s.len(), |(i, _)| i);
+                let rest = s.split_off(split_at);
+                StreamData::Text(std::mem::replace(s, rest))
+            }
+            StreamData::Bytes(b) => {
+                let rest = b.split_off(n.map_or(b.len(), |n| n.min(b.len())));
+                StreamData::Bytes(std::mem::replace(b, rest))
+            }
+        }
+    }
+
+    /// Append data obtained from the source to the buffered data.
+    fn extend_buffer(&mut self, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        let Some(data) = FeedData::from_py(data)? else {
+            return Err(PyTypeError::new_err(format!(
+                "unsuitable stream data type '{}'",
+                data.get_type()
+            )));
+        };
+        let buffer_is_empty = self.buffered_len() == 0;
+        match (&mut self.buffer, data) {
+            (StreamData::Text(s), FeedData::Text(t)) => s.push_str(&t),
+            (StreamData::Bytes(b), FeedData::Bytes(c)) => b.extend(c),
+            // the type of an empty buffer is just a guess, so it can change
+            (buffer, FeedData::Text(t)) if buffer_is_empty => *buffer = StreamData::Text(t),
+            (buffer, FeedData::Bytes(c)) if buffer_is_empty => *buffer = StreamData::Bytes(c),
+            _ => {
+                return Err(PyTypeError::new_err(
+                    "source returned data of a different type than before",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Pull chunks from an iterator source into the buffer until it holds at least `n` chars or
+    /// bytes (all remaining chunks if `None`).
+    fn pull_chunks(&mut self, iterator: &Bound<'_, PyIterator>, n: Option<usize>) -> PyResult<()> {
+        while !matches!(n, Some(n) if self.buffered_len() >= n) {
+            match iterator.clone().next() {
+                Some(chunk) => self.extend_buffer(&chunk?)?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ContinuationStream {
+    /// Read and return up to `size` chars or bytes, or all remaining data if
+    /// `size` is negative or None.
+    #[pyo3(signature = (size = -1, /))]
+    fn read<'py>(&mut self, py: Python<'py>, size: Option<isize>) -> PyResult<Bound<'py, PyAny>> {
+        if self.closed {
+            return Err(PyValueError::new_err("I/O operation on closed file."));
+        }
+        let size = size.and_then(|size| usize::try_from(size).ok());
+        let Some(source) = self.source.as_ref().map(|s| s.bind(py).clone()) else {
+            return self.take_buffered(size).into_pyobject(py);
+        };
+        // (file objects are iterators, too, so this has to be checked first)
+        if !source.hasattr("read")? {
+            self.pull_chunks(source.cast::<PyIterator>()?, size)?;
+            return self.take_buffered(size).into_pyobject(py);
+        }
+        let buffered_len = self.buffered_len();
+        let to_read_size = |n: Option<usize>| n.map_or(-1, |n| n as isize);
+        if buffered_len == 0 {
+            // nothing buffered (anymore) => pass the source's data on as it is
+            return source.call_method1("read", (to_read_size(size),));
+        }
+        let data = self.take_buffered(size);
+        let n_missing = size.map(|n| n.saturating_sub(buffered_len));
+        if n_missing == Some(0) {
+            return data.into_pyobject(py);
+        }
+        // (everything buffered has been taken, so this just puts it back to append to it)
+        self.buffer = data;
+        let more = source.call_method1("read", (to_read_size(n_missing),))?;
+        // None means that no data is available right now (non-blocking streams)
+        if !more.is_none() {
+            self.extend_buffer(&more)?;
+        }
+        self.take_buffered(None).into_pyobject(py)
+    }
+
+    fn readable(&self) -> bool {
+        true
+    }
+
+    fn seekable(&self) -> bool {
+        false
+    }
+
+    fn writable(&self) -> bool {
+        false
+    }
+
+    /// Close this object, discarding buffered data. The source is left open.
+    fn close(&mut self) {
+        self.closed = true;
+        self.buffer = StreamData::Bytes(Vec::new());
+        self.source = None;
+    }
+
+    #[getter]
+    fn closed(&self) -> bool {
+        self.closed
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) {
+        self.close();
+    }
+}
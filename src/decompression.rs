@@ -0,0 +1,196 @@
+/// Decompression of compressed input streams.
+use pyo3::exceptions::PyValueError;
+use pyo3::{PyResult, Python};
+use std::io;
+use std::io::{Cursor, Read};
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    const MAX_MAGIC_LEN: usize = 6;
+
+    /// Determine the compression format from the magic bytes at the start of the data.
+    fn detect(magic: &[u8]) -> Option<Self> {
+        if magic.starts_with(b"\x1f\x8b") {
+            Some(Compression::Gzip)
+        } else if magic.starts_with(b"\x28\xb5\x2f\xfd") {
+            Some(Compression::Zstd)
+        } else if magic.starts_with(b"BZh") {
+            Some(Compression::Bzip2)
+        } else if magic.starts_with(b"\xfd7zXZ\x00") {
+            Some(Compression::Xz)
+        } else {
+            None
+        }
+    }
+
+    fn decoder<R: Read + Send + 'static>(self, inner: R) -> io::Result<Box<dyn Read + Send>> {
+        Ok(match self {
+            Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(inner)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(inner)?),
+            Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(inner)),
+            Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(inner)),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompressionMode {
+    /// Detect the compression format from the data's magic bytes, passing it through as-is if
+    /// there is no known one.
+    Auto,
+    Fixed(Compression),
+}
+
+impl CompressionMode {
+    /// Parse the value of the tokenizer's `compression` argument.
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        Ok(match name {
+            "auto" => CompressionMode::Auto,
+            "gzip" => CompressionMode::Fixed(Compression::Gzip),
+            "zstd" => CompressionMode::Fixed(Compression::Zstd),
+            "bz2" => CompressionMode::Fixed(Compression::Bzip2),
+            "xz" => CompressionMode::Fixed(Compression::Xz),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown compression '{name}', must be one of 'auto', 'gzip', 'zstd', 'bz2' \
+                    or 'xz'"
+                )))
+            }
+        })
+    }
+}
+
+/// Numbers of compressed bytes read from a stream and decompressed bytes obtained from them.
+#[derive(Default)]
+pub struct CompressionOffsets {
+    compressed: AtomicU64,
+    decompressed: AtomicU64,
+}
+
+impl CompressionOffsets {
+    pub fn compressed(&self) -> u64 {
+        self.compressed.load(Ordering::Relaxed)
+    }
+
+    pub fn decompressed(&self) -> u64 {
+        self.decompressed.load(Ordering::Relaxed)
+    }
+}
+
+/// Handle to `CompressionOffsets` that is shared between the tokenizer and its stream.
+pub type SharedCompressionOffsets = Arc<CompressionOffsets>;
+
+/// Reader that counts the (compressed) bytes read from its inner reader.
+struct CountingReader<R> {
+    inner: R,
+    offsets: SharedCompressionOffsets,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n_bytes_read = self.inner.read(buf)?;
+        self.offsets
+            .compressed
+            .fetch_add(n_bytes_read as u64, Ordering::Relaxed);
+        Ok(n_bytes_read)
+    }
+}
+
+/// Decoder of a `DecompressingReader`.
+enum Decoder {
+    /// Compression format not detected yet because nothing has been read so far (or the magic
+    /// bytes have only been read in part, e.g. from a non-blocking stream).
+    Detecting {
+        inner: Box<dyn Read + Send>,
+        magic: Vec<u8>,
+    },
+    Decoding(Box<dyn Read + Send>),
+}
+
+/// Reader that decompresses the data read from its inner reader.
+///
+/// Decompression happens with the GIL released, so other Python threads can run in the meantime
+/// (reading from Python streams temporarily reacquires it, of course). In `Auto` mode, the magic
+/// bytes are only read once data is first requested, so creating the reader never blocks.
+pub struct DecompressingReader {
+    // the mutex is never locked, it's only there to make this Sync
+    decoder: Mutex<Decoder>,
+    offsets: SharedCompressionOffsets,
+}
+
+impl DecompressingReader {
+    pub fn new<R: Read + Send + 'static>(
+        inner: R,
+        mode: CompressionMode,
+        offsets: SharedCompressionOffsets,
+    ) -> io::Result<Self> {
+        let inner = CountingReader {
+            inner,
+            offsets: offsets.clone(),
+        };
+        let decoder = match mode {
+            CompressionMode::Fixed(compression) => Decoder::Decoding(compression.decoder(inner)?),
+            CompressionMode::Auto => Decoder::Detecting {
+                inner: Box::new(inner),
+                magic: Vec::with_capacity(Compression::MAX_MAGIC_LEN),
+            },
+        };
+        Ok(DecompressingReader {
+            decoder: Mutex::new(decoder),
+            offsets,
+        })
+    }
+}
+
+impl Decoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Decoder::Detecting { inner, magic } = self {
+            // magic bytes read so far are kept if this fails, so it can simply be retried
+            while magic.len() < Compression::MAX_MAGIC_LEN {
+                let mut chunk = [0; Compression::MAX_MAGIC_LEN];
+                match inner.read(&mut chunk[..Compression::MAX_MAGIC_LEN - magic.len()]) {
+                    Ok(0) => break,
+                    Ok(n_bytes_read) => magic.extend_from_slice(&chunk[..n_bytes_read]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            let Decoder::Detecting { inner, magic } =
+                mem::replace(self, Decoder::Decoding(Box::new(io::empty())))
+            else {
+                unreachable!("checked above")
+            };
+            let compression = Compression::detect(&magic);
+            let inner = Cursor::new(magic).chain(inner);
+            *self = Decoder::Decoding(match compression {
+                Some(compression) => compression.decoder(inner)?,
+                None => Box::new(inner),
+            });
+        }
+        match self {
+            Decoder::Decoding(decoder) => decoder.read(buf),
+            Decoder::Detecting { .. } => unreachable!("detection finished above"),
+        }
+    }
+}
+
+impl Read for DecompressingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let decoder = self.decoder.get_mut().unwrap_or_else(|e| e.into_inner());
+        let n_bytes_read = Python::attach(|py| py.detach(|| decoder.read(buf)))?;
+        self.offsets
+            .decompressed
+            .fetch_add(n_bytes_read as u64, Ordering::Relaxed);
+        Ok(n_bytes_read)
+    }
+}
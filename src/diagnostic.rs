@@ -0,0 +1,33 @@
+/// Errors that were recovered from instead of being raised as exceptions.
+use pyo3::prelude::*;
+
+/// Description of a JSON syntax error that the tokenizer recovered from.
+///
+/// Attributes:
+///   message: Description of the error.
+///   index: Index of the character at which the error was detected.
+///   record: Number of the record in which the error occurred, or None if
+///     the input isn't split into records.
+#[pyclass(frozen, get_all)]
+pub struct Diagnostic {
+    pub message: String,
+    pub index: i64,
+    pub record: Option<u64>,
+}
+
+#[pymethods]
+impl Diagnostic {
+    fn __repr__(&self) -> String {
+        let record = match self.record {
+            Some(record) => record.to_string(),
+            None => "None".to_string(),
+        };
+        format!(
+            "Diagnostic(message={:?}, index={}, record={record})",
+            self.message, self.index
+        )
+    }
+    fn __str__(&self) -> String {
+        format!("{} at index {}", self.message, self.index)
+    }
+}
@@ -0,0 +1,82 @@
+/// Iterators over the documents in a stream of several concatenated JSON documents.
+use crate::{RustTokenizer, TokenType};
+use pyo3::prelude::*;
+
+/// Iterator over the documents in a multi-document stream.
+///
+/// Returned by `RustTokenizer.documents()`. Yields one `DocumentTokenIterator` per document.
+#[pyclass]
+pub struct DocumentIterator {
+    tokenizer: Py<RustTokenizer>,
+}
+
+impl DocumentIterator {
+    pub(crate) fn new(tokenizer: Py<RustTokenizer>) -> Self {
+        DocumentIterator { tokenizer }
+    }
+}
+
+#[pymethods]
+impl DocumentIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    fn __next__(slf: PyRef<'_, Self>, py: Python<'_>) -> PyResult<Option<DocumentTokenIterator>> {
+        let tokenizer = slf.tokenizer.bind(py);
+        // (the tokenizer might be in use in another thread, which has to result in a RuntimeError
+        // rather than a panic, hence the try_borrow*() calls)
+        // invalidate iterators over previous documents
+        tokenizer.try_borrow_mut()?.generation += 1;
+        // skip whatever is left of the previous document
+        while tokenizer.try_borrow()?.document_open {
+            if RustTokenizer::__next__(tokenizer.try_borrow_mut()?, py)?.is_none() {
+                break;
+            }
+        }
+        let mut tokenizer_ref = tokenizer.try_borrow_mut()?;
+        if !RustTokenizer::skip_whitespace(&mut tokenizer_ref)? {
+            return Ok(None);
+        }
+        // the document's first char hasn't been processed yet, so the tokenizer doesn't know yet
+        // that it has started, which would keep it from being skipped if none of its tokens are
+        // consumed before the next document is requested
+        tokenizer_ref.document_open = true;
+        Ok(Some(DocumentTokenIterator {
+            tokenizer: slf.tokenizer.clone_ref(py),
+            generation: tokenizer_ref.generation,
+            exhausted: false,
+        }))
+    }
+}
+
+/// Iterator over the tokens of a single document in a multi-document stream.
+///
+/// Stops yielding tokens once the end of its document is reached or the next document has been
+/// requested from the `DocumentIterator` that created it.
+#[pyclass]
+pub struct DocumentTokenIterator {
+    tokenizer: Py<RustTokenizer>,
+    generation: u64,
+    exhausted: bool,
+}
+
+#[pymethods]
+impl DocumentTokenIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    fn __next__(
+        mut slf: PyRefMut<'_, Self>,
+        py: Python<'_>,
+    ) -> PyResult<Option<(TokenType, Option<Py<PyAny>>)>> {
+        let tokenizer = slf.tokenizer.bind(py).clone();
+        if slf.exhausted || tokenizer.try_borrow()?.generation != slf.generation {
+            return Ok(None);
+        }
+        let token = RustTokenizer::__next__(tokenizer.try_borrow_mut()?, py)?;
+        if token.is_none() || !tokenizer.try_borrow()?.document_open {
+            slf.exhausted = true;
+        }
+        Ok(token)
+    }
+}
@@ -0,0 +1,146 @@
+/// Reading from the OS-level file descriptors of Python file objects.
+use crate::py_common::PySeekWhence;
+use crate::py_err::TracebackDisplay;
+use pyo3::types::{PyAny, PyAnyMethods};
+use pyo3::{Bound, Py, PyResult, Python};
+use std::fs::File;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::fd::{BorrowedFd, RawFd};
+use std::os::unix::fs::FileExt;
+
+/// Reader for the file descriptor underlying a Python file object, bypassing the object itself.
+///
+/// Reads are performed with the GIL released, so other Python threads can run in the meantime.
+///
+/// For seekable files, reading starts at the object's current position and uses positioned reads
+/// (`pread`), so the position of the file descriptor itself is left alone. Reading and seeking
+/// only change our own position, which is then set as the object's position as well so that it
+/// is correct from Python's perspective, too.
+pub struct FdReader {
+    stream: Py<PyAny>,
+    // duplicate of the file descriptor so it stays valid even if the object is closed
+    file: File,
+    // None for unseekable files (e.g. pipes)
+    pos: Option<u64>,
+}
+
+impl FdReader {
+    /// Create a reader for the given Python file object if it's one we can safely bypass.
+    ///
+    /// That is the case for exact instances of `io.FileIO` as well as seekable instances of
+    /// `io.BufferedReader` and `io.BufferedRandom`, whose read-ahead buffer is accounted for by
+    /// starting at their logical position (unseekable buffered streams may already have consumed
+    /// data from the file descriptor that we have no way of getting at). Subclasses are excluded
+    /// because they could override `read()`. Returns `None` for all other objects.
+    pub fn new(stream: &Bound<'_, PyAny>, seekable: bool) -> PyResult<Option<Self>> {
+        let py = stream.py();
+        let io_module = py.import("io")?;
+        let stream_type = stream.get_type();
+        let bypassable = if stream_type.is(&io_module.getattr("FileIO")?) {
+            true
+        } else if stream_type.is(&io_module.getattr("BufferedReader")?)
+            || stream_type.is(&io_module.getattr("BufferedRandom")?)
+        {
+            seekable
+        } else {
+            false
+        };
+        if !bypassable {
+            return Ok(None);
+        }
+        let fd = stream.call_method0("fileno")?.extract::<RawFd>()?;
+        let pos = if seekable {
+            // make sure pending writes (BufferedRandom) are visible to us
+            stream.call_method0("flush")?;
+            Some(stream.call_method0("tell")?.extract::<u64>()?)
+        } else {
+            None
+        };
+        // SAFETY: The file descriptor was just returned by the object's fileno(), so it's open
+        // (the GIL has been held since) and remains so for the duration of this borrow.
+        let file = File::from(unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()?);
+        Ok(Some(FdReader {
+            stream: stream.clone().unbind(),
+            file,
+            pos,
+        }))
+    }
+
+    /// Position up to which data has been read, or `None` for unseekable files.
+    pub fn pos(&self) -> Option<u64> {
+        self.pos
+    }
+
+    /// Another handle to the file, e.g. for reading from it in another thread.
+    pub fn try_clone_file(&self) -> io::Result<File> {
+        self.file.try_clone()
+    }
+
+    /// Set the Python file object's position.
+    fn set_stream_pos(&self, py: Python<'_>, pos: u64) -> io::Result<()> {
+        self.stream
+            .bind(py)
+            .call_method1("seek", (pos, PySeekWhence::Set))
+            .map(|_| ())
+            .map_err(|e| {
+                io::Error::other(format!(
+                    "Error seeking to offset {} in Python file object: {}\n{}",
+                    pos,
+                    e,
+                    e.traceback_display(),
+                ))
+            })
+    }
+}
+
+impl Read for FdReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Python::attach(|py| loop {
+            let result = py.detach(|| match self.pos {
+                Some(pos) => self.file.read_at(buf, pos),
+                None => (&self.file).read(buf),
+            });
+            match result {
+                Ok(n_bytes_read) => {
+                    if let Some(pos) = self.pos {
+                        let new_pos = pos + n_bytes_read as u64;
+                        self.set_stream_pos(py, new_pos)?;
+                        self.pos = Some(new_pos);
+                    }
+                    return Ok(n_bytes_read);
+                }
+                // allow interrupting reads that take long (e.g. from pipes) via Ctrl+C
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                    py.check_signals().map_err(io::Error::other)?
+                }
+                Err(e) => return Err(e),
+            }
+        })
+    }
+}
+
+impl Seek for FdReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let Some(cur) = self.pos else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "can't seek in unseekable file",
+            ));
+        };
+        let new_pos = match pos {
+            SeekFrom::Start(x) => Some(x),
+            SeekFrom::Current(x) => cur.checked_add_signed(x),
+            SeekFrom::End(x) => self.file.metadata()?.len().checked_add_signed(x),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Python::attach(|py| self.set_stream_pos(py, new_pos))?;
+        self.pos = Some(new_pos);
+        Ok(new_pos)
+    }
+}
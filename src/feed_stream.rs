@@ -0,0 +1,251 @@
+/// Char source for data that is pushed into the tokenizer instead of being read from a stream.
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use owned_chars::{OwnedChars, OwnedCharsExt};
+use pyo3::types::{PyAny, PyBytes, PyBytesMethods, PyString, PyStringMethods};
+use pyo3::{Bound, PyResult};
+use std::io;
+use std::str;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use thiserror::Error;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FeedDataType {
+    Text,
+    Bytes,
+}
+
+/// Data to feed into a `FeedBuffer`.
+pub enum FeedData {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl FeedData {
+    /// Convert a Python `str` or `bytes` object, returning `None` for objects of other types.
+    pub fn from_py(data: &Bound<'_, PyAny>) -> PyResult<Option<Self>> {
+        Ok(if let Ok(s) = data.cast::<PyString>() {
+            Some(FeedData::Text(s.to_str()?.to_owned()))
+        } else if let Ok(b) = data.cast::<PyBytes>() {
+            Some(FeedData::Bytes(b.as_bytes().to_vec()))
+        } else {
+            None
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FeedError {
+    #[error("feed() called after close()")]
+    Closed,
+    #[error("feed() called after the tokenizer was consumed by into_stream()")]
+    Consumed,
+    #[error("can't feed {0} after having fed {1} before")]
+    MixedTypes(&'static str, &'static str),
+    #[error("invalid UTF-8 in fed data: {0}")]
+    InvalidUtf8(str::Utf8Error),
+    #[error("fed data ends in the middle of a UTF-8 sequence")]
+    TruncatedUtf8,
+}
+
+impl FeedDataType {
+    fn name(self) -> &'static str {
+        match self {
+            FeedDataType::Text => "str",
+            FeedDataType::Bytes => "bytes",
+        }
+    }
+}
+
+/// Data that has been fed but not yet read.
+#[derive(Default)]
+pub struct FeedBuffer {
+    text: String,
+    read_pos: usize,        // byte offset in `text` up to which it has been read
+    pending_bytes: Vec<u8>, // incomplete UTF-8 sequence at the end of the bytes fed so far
+    data_type: Option<FeedDataType>,
+    closed: bool,
+    consumed: bool, // handed over to `into_stream()`, which implies `closed`
+}
+
+impl FeedBuffer {
+    /// Buffer that has been closed without any data having been fed.
+    pub fn exhausted() -> Self {
+        FeedBuffer {
+            closed: true,
+            ..FeedBuffer::default()
+        }
+    }
+
+    /// Buffer of a tokenizer whose remaining data has been handed over to `into_stream()`.
+    pub fn consumed() -> Self {
+        FeedBuffer {
+            consumed: true,
+            ..FeedBuffer::exhausted()
+        }
+    }
+
+    pub fn feed(&mut self, data: FeedData) -> Result<(), FeedError> {
+        if self.consumed {
+            return Err(FeedError::Consumed);
+        }
+        if self.closed {
+            return Err(FeedError::Closed);
+        }
+        let data_type = match data {
+            FeedData::Text(_) => FeedDataType::Text,
+            FeedData::Bytes(_) => FeedDataType::Bytes,
+        };
+        if let Some(previous) = self.data_type {
+            if previous != data_type {
+                return Err(FeedError::MixedTypes(data_type.name(), previous.name()));
+            }
+        }
+        // make room by dropping the data that has already been read
+        self.text.drain(..self.read_pos);
+        self.read_pos = 0;
+        match data {
+            FeedData::Text(s) => self.text.push_str(&s),
+            FeedData::Bytes(b) => {
+                // rejected data must leave the buffer untouched, so it's validated separately
+                let mut valid = [self.pending_bytes.as_slice(), &b].concat();
+                let valid_up_to = match str::from_utf8(&valid) {
+                    Ok(s) => s.len(),
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    Err(e) => return Err(FeedError::InvalidUtf8(e)),
+                };
+                self.pending_bytes = valid.split_off(valid_up_to);
+                // can't fail because we've just checked it
+                if let Ok(s) = str::from_utf8(&valid) {
+                    self.text.push_str(s);
+                }
+            }
+        }
+        self.data_type = Some(data_type);
+        Ok(())
+    }
+
+    pub fn close(&mut self) -> Result<(), FeedError> {
+        self.closed = true;
+        if self.pending_bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(FeedError::TruncatedUtf8)
+        }
+    }
+
+    /// Take the next char out of the buffer.
+    ///
+    /// Returns an error of kind `WouldBlock` if no data is available unless the buffer has been
+    /// closed, in which case EOF is signified as usual.
+    pub fn read_char(&mut self) -> io::Result<Option<char>> {
+        match self.text[self.read_pos..].chars().next() {
+            Some(c) => {
+                self.read_pos += c.len_utf8();
+                Ok(Some(c))
+            }
+            None => self.no_data(),
+        }
+    }
+
+    /// Take all data that hasn't been read yet out of the buffer.
+    ///
+    /// Returns an error of kind `WouldBlock` if no data is available unless the buffer has been
+    /// closed, in which case EOF is signified by `None`.
+    pub fn take_text(&mut self) -> io::Result<Option<String>> {
+        if self.read_pos >= self.text.len() {
+            return self.no_data();
+        }
+        let text = self.text.split_off(self.read_pos);
+        self.text.clear();
+        self.read_pos = 0;
+        Ok(Some(text))
+    }
+
+    fn no_data<T>(&self) -> io::Result<Option<T>> {
+        if self.closed {
+            Ok(None)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "no data has been fed yet",
+            ))
+        }
+    }
+
+    /// Data that has been fed but not yet read, in the type it was fed as.
+    ///
+    /// `taken` is data that has been taken out of the buffer but not read yet, which precedes it.
+    pub fn remainder(&self, taken: &str) -> StreamData {
+        let s = [taken, &self.text[self.read_pos..]].concat();
+        match self.data_type {
+            Some(FeedDataType::Bytes) => {
+                let mut b = s.into_bytes();
+                b.extend(&self.pending_bytes);
+                StreamData::Bytes(b)
+            }
+            _ => StreamData::Text(s),
+        }
+    }
+}
+
+/// Handle to a `FeedBuffer` that is shared between the tokenizer and its `FeedStream`.
+pub type SharedFeedBuffer = Arc<Mutex<FeedBuffer>>;
+
+pub fn lock_feed_buffer(buffer: &SharedFeedBuffer) -> MutexGuard<'_, FeedBuffer> {
+    // the buffer is always left in a consistent state, so poisoning doesn't matter
+    buffer.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// "Suitable" stream (see `SuitableStream`) that reads data from a `FeedBuffer`.
+///
+/// Reading a char when no data is available returns an error of kind `WouldBlock` unless the
+/// buffer has been closed, in which case EOF is signified as usual. All data available at that
+/// point is taken out of the buffer at once, so it only has to be locked once per fed chunk
+/// rather than once per char.
+pub struct FeedStream {
+    buffer: SharedFeedBuffer,
+    // data taken out of the buffer that hasn't been read yet
+    chars_iter: OwnedChars,
+}
+
+impl FeedStream {
+    pub fn new(buffer: SharedFeedBuffer) -> Self {
+        FeedStream {
+            buffer,
+            chars_iter: OwnedChars::from_string("".to_owned()),
+        }
+    }
+
+    /// Stream that is at EOF from the start.
+    pub fn exhausted() -> Self {
+        FeedStream::new(Arc::new(Mutex::new(FeedBuffer::exhausted())))
+    }
+}
+
+impl Utf8CharSource for FeedStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        if let Some(c) = self.chars_iter.next() {
+            return Ok(Some(c));
+        }
+        let text = lock_feed_buffer(&self.buffer).take_text()?;
+        Ok(text.and_then(|text| {
+            self.chars_iter = OwnedCharsExt::into_chars(text);
+            self.chars_iter.next()
+        }))
+    }
+}
+
+impl ParkCursorChars for FeedStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        // no-op
+        Ok(())
+    }
+}
+
+impl Remainder for FeedStream {
+    fn remainder(&self) -> StreamData {
+        lock_feed_buffer(&self.buffer).remainder(self.chars_iter.as_str())
+    }
+}
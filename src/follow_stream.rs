@@ -0,0 +1,82 @@
+/// Stream wrapper that waits for more data at EOF, for following files that are still growing.
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::suitable_stream::SuitableStream;
+use crate::utf8_char_source::Utf8CharSource;
+use pyo3::Python;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// "Suitable" stream (see `SuitableStream`) that polls its inner stream for more data instead
+/// of reporting EOF, like `tail -f`.
+///
+/// The inner stream must be set up so that it doesn't consider EOF to be final and can be polled
+/// again afterwards. Both EOF and missing data on non-blocking streams (errors of kind
+/// `WouldBlock`) cause polling, regardless of where in the JSON data they occur, as writers can
+/// stop anywhere. If no new data arrives within the timeout (if any), which is measured anew for
+/// each char that is waited for, EOF is reported after all.
+pub struct FollowStream {
+    inner: Box<dyn SuitableStream + Send + Sync>,
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+}
+
+impl FollowStream {
+    pub fn new(
+        inner: Box<dyn SuitableStream + Send + Sync>,
+        poll_interval: Duration,
+        timeout: Option<Duration>,
+    ) -> Self {
+        FollowStream {
+            inner,
+            poll_interval,
+            timeout,
+        }
+    }
+
+    fn wait(&self) -> io::Result<()> {
+        Python::attach(|py| {
+            py.detach(|| thread::sleep(self.poll_interval));
+            // allow interrupting the wait, e.g. via Ctrl+C
+            py.check_signals()
+        })
+        .map_err(io::Error::other)
+    }
+}
+
+impl Utf8CharSource for FollowStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        let start = Instant::now();
+        loop {
+            match self.inner.read_char() {
+                Ok(Some(c)) => return Ok(Some(c)),
+                Ok(None) => (),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => (),
+                Err(e) => return Err(e),
+            }
+            if let Some(timeout) = self.timeout {
+                if start.elapsed() >= timeout {
+                    return Ok(None);
+                }
+            }
+            self.wait()?;
+        }
+    }
+}
+
+impl ParkCursorChars for FollowStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        self.inner.park_cursor()
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        self.inner.park_cursor_before(unread)
+    }
+}
+
+impl Remainder for FollowStream {
+    fn remainder(&self) -> StreamData {
+        self.inner.remainder()
+    }
+}
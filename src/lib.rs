@@ -4,28 +4,60 @@
 /// json-stream's tokenizer was originally taken from the NAYA project.
 /// https://github.com/danielyule/naya
 /// Copyright (c) 2019 Daniel Yule
+use crate::continuation_stream::ContinuationStream;
+use crate::decompression::{CompressionMode, SharedCompressionOffsets};
+use crate::diagnostic::Diagnostic;
+use crate::documents::DocumentIterator;
+use crate::feed_stream::{lock_feed_buffer, FeedBuffer, FeedData, FeedStream, SharedFeedBuffer};
+use crate::follow_stream::FollowStream;
 use crate::int::{AppropriateInt, ParseIntError};
+use crate::mmap_stream::MmapStream;
+use crate::py_str_stream::PyStrStream;
+use crate::record_format::{RecordFormat, TruncatedValueWarning, RECORD_SEPARATOR};
 use crate::remainder::StreamData;
 use crate::suitable_stream::{make_suitable_stream, SuitableStream};
+use crate::tokenizer_options::TokenizerOptions;
 use compact_str::CompactString;
-use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::exceptions::{PyBlockingIOError, PyIOError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyInt;
+use pyo3::types::{PyDict, PyInt, PyString, PyType};
 use std::borrow::BorrowMut;
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::io;
 use std::num::ParseFloatError;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
 use unwrap_infallible::UnwrapInfallible;
 
+mod buffer_size;
+mod continuation_stream;
+mod decompression;
+mod diagnostic;
+mod documents;
+#[cfg(unix)]
+mod fd_reader;
+mod feed_stream;
+mod follow_stream;
 mod int;
+mod mmap_stream;
 mod opaque_seek;
 mod park_cursor;
+#[cfg(unix)]
+mod prefetch_stream;
+mod py_buffer_stream;
 mod py_bytes_stream;
+mod py_chunk_stream;
 mod py_common;
 mod py_err;
+mod py_str_stream;
 mod py_text_stream;
 mod read_string;
+mod record_format;
 mod remainder;
+mod suitable_peekable_buffered_bytes_stream;
 mod suitable_seekable_buffered_bytes_stream;
 mod suitable_seekable_buffered_text_stream;
 mod suitable_stream;
@@ -33,7 +65,9 @@ mod suitable_unbuffered_bytes_stream;
 mod suitable_unbuffered_text_stream;
 mod suitable_unseekable_buffered_bytes_stream;
 mod suitable_unseekable_buffered_text_stream;
+mod tokenizer_options;
 mod utf8_char_source;
+mod utf8_reader;
 
 mod char_or_eof;
 use crate::char_or_eof::CharOrEof;
@@ -42,7 +76,7 @@ use CharOrEof::{Char, Eof};
 mod unicode_utils;
 use crate::unicode_utils::{decode_surrogate_pair, is_surrogate, UnicodeError};
 
-use crate::suitable_stream::BufferingMode;
+use crate::suitable_stream::{BufferingArg, BufferingMode, StreamMode};
 
 #[derive(Clone)]
 enum TokenType {
@@ -51,6 +85,9 @@ enum TokenType {
     Number = 2,
     Boolean = 3,
     Null = 4,
+    Error_ = 5,
+    RecordEnd = 6,
+    ValueStart = 7,
 }
 
 #[derive(Clone)]
@@ -89,23 +126,142 @@ enum State {
     Inf5 = 32,
     Inf6 = 33,
     Inf7 = 34,
+    SkipRecord = 35,
+    TopLevelLiteralEnd = 36,
+    SkipToDelimiter = 37,
+}
+
+impl State {
+    /// Whether this is a state within (or right after) a number or literal.
+    fn is_scalar(&self) -> bool {
+        !matches!(
+            self,
+            State::Whitespace
+                | State::String_
+                | State::StringEscape
+                | State::StringEnd
+                | State::Unicode
+                | State::UnicodeSurrogateStart
+                | State::UnicodeSurrogateStringEscape
+                | State::UnicodeSurrogate
+                | State::SkipRecord
+                | State::SkipToDelimiter
+        )
+    }
 }
 
 /// A drop-in replacement for json-stream's JSON tokenizer, written in Rust.
 ///
+/// Tokenizers can be used from any thread, also on free-threaded Python
+/// builds, but a single tokenizer must not be used from several threads at
+/// the same time: Attempts to do so raise a `RuntimeError` in the thread
+/// that got there second.
+///
 /// Args:
 ///   stream: Python file-like object / stream to read JSON from. Can be
 ///     either in text mode or in binary mode (so long as the bytes are valid
-///     UTF-8).
+///     UTF-8). If the stream is non-blocking and no data is available from
+///     it yet, a `BlockingIOError` is raised, after which iteration can be
+///     resumed once data is available. Alternatively, `str` and `bytes`
+///     objects are tokenized in place without copying (see also
+///     `from_str()`), other objects supporting the buffer protocol
+///     (`bytearray`, `memoryview`, `mmap`, ...) are copied up front and then
+///     tokenized, and objects without a `read` method are treated as
+///     iterables of `str` or `bytes` chunks (e.g. the ones returned by
+///     `requests.Response.iter_content()`), which may split UTF-8 sequences
+///     at arbitrary points.
 ///   buffering: Internal buffer size. -1 (the default) means to let the
-///     implementation choose a buffer size. Can conflict with `correct_cursor`.
+///     implementation choose a buffer size. `"adaptive"` means to start with
+///     a small buffer, so the first tokens of interactive streams are
+///     available quickly, and double its size with each read up to a limit,
+///     so large files are still read efficiently. Can conflict with
+///     `correct_cursor`. Has no effect on buffer objects and iterables of
+///     chunks.
 ///   correct_cursor: *(not part of API yet, may be removed at any point)*
 ///     Whether it is required that the cursor is left in the correct position
 ///     (behind the last processed character) after park_cursor() has been
 ///     called. If set to False, performance for unseekable streams is
 ///     drastically improved at the cost of the cursor ending up in places
-///     unrelated to the actual tokenization progress. For seekable streams, the
-///     improvement shouldn't be noticable.
+///     unrelated to the actual tokenization progress. For seekable streams and
+///     binary streams supporting `peek()` and `read1()` (like
+///     `io.BufferedReader`), the improvement shouldn't be noticable.
+///   ndjson: Whether to treat the stream as newline-delimited JSON (JSON
+///     Lines), i.e. as a sequence of records separated by newlines. If set,
+///     the end of each record is signified by a token of type
+///     `TokenType.RecordEnd` whose value is the record number (the 0-based
+///     line number), and a malformed record results in a token of type
+///     `TokenType.Error_` with a `Diagnostic` as its value instead of an
+///     exception, after which tokenization resumes at the next line. Tokens
+///     of a malformed record that were emitted before the error token should
+///     be discarded.
+///   json_seq: Whether to treat the stream as an RFC 7464 JSON text sequence
+///     (application/json-seq), i.e. as a sequence of records each preceded by
+///     an ASCII record separator character (U+001E). Record ends and
+///     malformed records are signified the same way as in `ndjson` mode,
+///     except that records are only counted if they contain anything. As
+///     mandated by the RFC, a top-level number or literal that isn't followed
+///     by whitespace before the next record separator or the end of the
+///     stream may have been truncated, so it is discarded and a
+///     `TruncatedValueWarning` is emitted. Mutually exclusive with `ndjson`.
+///   recover: Whether to recover from JSON syntax errors instead of raising
+///     an exception. If set, each error is appended to `errors` as a
+///     `Diagnostic` and tokenization resumes at the next structural character
+///     (one of `{}[]:,`), discarding the offending token. In `ndjson` and
+///     `json_seq` modes, errors are always recovered from per record, so this
+///     only controls whether they are also collected in `errors`.
+///   emit_error_tokens: Whether to emit a token of type `TokenType.Error_`
+///     with the `Diagnostic` as its value for each error recovered from in
+///     `recover` mode. Has no effect if `recover` isn't set.
+///   scan: Whether to extract JSON objects and arrays embedded in arbitrary
+///     text (e.g. log lines or Markdown). If set, anything outside of objects
+///     and arrays is skipped and each extracted value is preceded by a token
+///     of type `TokenType.ValueStart` whose value is the index of its first
+///     character. If a candidate value turns out to be invalid, a token of
+///     type `TokenType.Error_` with a `Diagnostic` as its value is emitted
+///     (the candidate's preceding tokens should be discarded) and scanning
+///     resumes right after the candidate's opening bracket. Characters of a
+///     candidate value are kept in memory until it is complete. Mutually
+///     exclusive with `ndjson` and `json_seq`.
+///   allow_partial: Whether to emit best-effort tokens for strings, numbers
+///     and literals that are cut off by the end of the stream (e.g. a string
+///     token `"ab"` for `["ab` or a boolean token `True` for `[tr`) instead
+///     of raising an exception. Whether the last emitted token was such a
+///     partial token can be checked via `partial`, and `open_containers()`
+///     reports the objects and arrays that are still open. Useful for
+///     rendering JSON that is still being streamed.
+///   follow: Whether to keep waiting for more data when reaching the end of
+///     the stream, like `tail -f`, e.g. to tokenize log files that are still
+///     being written to. This happens wherever the current end of the stream
+///     is, i.e. not only between documents but also inside of them and in
+///     the middle of tokens, which are completed once the rest of them has
+///     been written.
+///   poll_interval: Number of seconds to wait between checks for more data
+///     in `follow` mode.
+///   follow_timeout: Number of seconds to wait for more data each time the
+///     end of the stream is reached in `follow` mode before considering it
+///     final, i.e. the longest pause in writing that is tolerated. Time spent
+///     outside of the tokenizer (e.g. processing tokens) doesn't count. None
+///     (the default) means to wait indefinitely.
+///   compression: Compression format of the stream's data, which is then
+///     decompressed on the fly: one of `"gzip"`, `"zstd"`, `"bz2"` or `"xz"`,
+///     or `"auto"` to detect it from the data's first bytes (uncompressed
+///     data is tokenized as-is). Only supported for binary file-like objects
+///     and incompatible with `correct_cursor` and `follow`. The progress of
+///     decompression is reported via `compressed_offset` and
+///     `decompressed_offset`.
+///   mode: Type of the data returned by the file-like object's `read()`
+///     method, `"bytes"` or `"text"`. None (the default) means to determine
+///     it by calling `read(0)`, which may not be desirable for custom streams.
+///   seekable: Whether the file-like object is seekable. None (the default)
+///     means to determine it by calling its `seekable()` method if it has
+///     one (objects without it are treated as unseekable).
+///   prefetch: Whether to read the next chunk of the stream in a background
+///     thread while the current one is being tokenized, which speeds up
+///     tokenizing files on slow storage. Only has an effect for buffered
+///     reading from seekable binary file objects whose file descriptor can be
+///     read from directly (`io.FileIO` and `io.BufferedReader`, e.g. from
+///     `open(..., "rb")` for regular files) on Unix and is ignored otherwise,
+///     including for pipes. Incompatible with `compression`.
 #[pyclass]
 struct RustTokenizer {
     stream: Box<dyn SuitableStream + Send + Sync>,
@@ -118,6 +274,28 @@ struct RustTokenizer {
     c: Option<char>,
     unicode_buffer: CompactString,
     prev_charcode: Option<u16>, // first half of a Unicode surrogate pair
+    containers: Vec<char>,      // opening brackets of currently open objects & arrays
+    document_open: bool,        // whether we're in the middle of a document
+    generation: u64,            // incremented when a new document is started via documents()
+    record_format: Option<RecordFormat>,
+    record: u64,                  // current record number (record modes)
+    record_value_done: bool,      // whether the current record's value is complete (record modes)
+    pending_token: Option<Token>, // top-level literal awaiting a delimiter (json_seq mode)
+    warning: Option<String>,      // warning to emit once control returns to Python
+    recover: bool,
+    emit_error_tokens: bool,
+    errors: Vec<Py<Diagnostic>>, // errors recovered from (recover mode)
+    scan: bool,
+    scan_start: Option<i64>, // index of the current candidate value's start (scan mode)
+    scan_history: Vec<char>, // chars of the current candidate value (scan mode)
+    replay: VecDeque<char>,  // chars to process again after backtracking (scan mode)
+    allow_partial: bool,
+    partial: bool, // whether the last emitted token was cut off by the end of the stream
+    feed_buffer: Option<SharedFeedBuffer>, // data fed via feed() (incremental tokenizers)
+    compression_offsets: Option<SharedCompressionOffsets>,
+    file_size: Option<u64>, // size of the memory-mapped file (tokenizers created via from_path)
+    source: Option<Py<PyAny>>, // object to continue reading from after the stream's data
+    reads_without_gil: bool, // whether to release the GIL while scanning long tokens
 }
 
 fn is_delimiter(c: CharOrEof) -> bool {
@@ -127,6 +305,18 @@ fn is_delimiter(c: CharOrEof) -> bool {
     }
 }
 
+fn parse_integer(s: &str) -> Result<AppropriateInt, ParsingError> {
+    match AppropriateInt::from_str(s) {
+        Ok(parsed_num) => Ok(parsed_num),
+        Err(ParseIntError::General(e)) => Err(ParsingError::InvalidJson(format!(
+            "Could not parse integer: {e}"
+        ))),
+        Err(ParseIntError::TooLargeOrSmall) => Err(ParsingError::Limitation(
+            "Incapable of parsing integer due to platform constraint".to_string(),
+        )),
+    }
+}
+
 impl<'py> IntoPyObject<'py> for TokenType {
     type Target = PyInt;
     type Output = Bound<'py, Self::Target>;
@@ -176,33 +366,220 @@ enum Token {
     Float(f64),
     Boolean(bool),
     Null,
+    RecordEnd(u64),
+    ValueStart(i64),
+}
+
+/// Result of processing a char, with the token (if any) converted to Python.
+type PyTokenResult = Result<Option<(TokenType, Option<Py<PyAny>>)>, ParsingError>;
+
+/// Number of chars scanned while holding the GIL before releasing it for the rest of a token.
+///
+/// Releasing and reacquiring the GIL isn't free, so it's only done for long tokens (e.g. large
+/// strings), where it doesn't matter: releasing it for every token made tokenizing a document
+/// of ~5M small tokens 20-40% slower, while the time spent on 100 strings of 100k chars each
+/// was the same either way. 1024 chars take well under a millisecond to scan, far less than
+/// Python's default thread switch interval of 5 ms.
+const MAX_CHARS_SCANNED_WITH_GIL: usize = 1024;
+
+/// Reason for `RustTokenizer::scan_chars` to stop scanning.
+enum Scanned {
+    /// Result of processing a char that needs to be handled with the GIL held.
+    Processed(Result<Option<Token>, ParsingError>),
+    /// No data available for now (incremental tokenizers).
+    NoData,
+    /// End of the stream.
+    Eof,
+    /// Maximum number of chars scanned.
+    MaxChars,
 }
 
 #[pymethods]
 impl RustTokenizer {
     #[new]
-    #[pyo3(signature = (stream, *, buffering = -1, correct_cursor = false))]
-    fn new(stream: Py<PyAny>, buffering: i64, correct_cursor: bool) -> PyResult<Self> {
-        let buffering_mode = if buffering < 0 {
-            BufferingMode::DontCare
-        } else if buffering == 0 || buffering == 1 {
-            BufferingMode::Unbuffered
-        } else {
-            BufferingMode::BufferedWithSize(buffering.try_into().unwrap())
-        };
-        let stream = make_suitable_stream(stream, buffering_mode, correct_cursor)?;
-        Ok(RustTokenizer {
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        stream,
+        *,
+        buffering = BufferingArg::Size(-1),
+        correct_cursor = false,
+        follow = false,
+        poll_interval = 0.1,
+        follow_timeout = None,
+        compression = None,
+        mode = None,
+        seekable = None,
+        prefetch = false,
+        **options,
+    ))]
+    fn new(
+        stream: Py<PyAny>,
+        buffering: BufferingArg,
+        correct_cursor: bool,
+        follow: bool,
+        poll_interval: f64,
+        follow_timeout: Option<f64>,
+        compression: Option<&str>,
+        mode: Option<&str>,
+        seekable: Option<bool>,
+        prefetch: bool,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let options = TokenizerOptions::from_kwargs(options)?;
+        let compression_mode = compression.map(CompressionMode::from_name).transpose()?;
+        let stream_mode = mode.map(StreamMode::from_name).transpose()?;
+        if compression_mode.is_some() && (correct_cursor || follow) {
+            return Err(PyValueError::new_err(
+                "compression can't be combined with correct_cursor or follow",
+            ));
+        }
+        if compression_mode.is_some() && prefetch {
+            return Err(PyValueError::new_err(
+                "compression can't be combined with prefetch",
+            ));
+        }
+        let compression_offsets = compression_mode.map(|_| SharedCompressionOffsets::default());
+        let poll_interval = Duration::try_from_secs_f64(poll_interval)
+            .map_err(|e| PyValueError::new_err(format!("invalid poll_interval: {e}")))?;
+        let follow_timeout = follow_timeout
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("invalid follow_timeout: {e}")))?;
+        let buffering_mode = BufferingMode::from_arg(buffering)?;
+        let stream_and_source = make_suitable_stream(
             stream,
-            completed: false,
-            advance: true,
-            token: String::new(),
-            state: State::Whitespace,
-            next_state: State::Whitespace,
-            index: -1,
-            c: None,
-            unicode_buffer: CompactString::with_capacity(4),
-            prev_charcode: None,
-        })
+            buffering_mode,
+            correct_cursor,
+            follow,
+            compression_mode.zip(compression_offsets.clone()),
+            stream_mode,
+            seekable,
+            prefetch,
+        )?;
+        let mut stream = stream_and_source.stream;
+        if follow {
+            stream = Box::new(FollowStream::new(stream, poll_interval, follow_timeout));
+        }
+        let mut tokenizer = RustTokenizer::with_suitable_stream(stream, options);
+        tokenizer.compression_offsets = compression_offsets;
+        tokenizer.source = stream_and_source.source;
+        tokenizer.reads_without_gil = stream_and_source.reads_without_gil;
+        Ok(tokenizer)
+    }
+    /// Create a tokenizer that is fed data via `feed()` instead of reading it from a stream.
+    ///
+    /// Iterating over the tokenizer yields all tokens that can be tokenized
+    /// from the data fed so far and then stops, even in the middle of a
+    /// token, so iteration can be resumed once more data has been fed. Once
+    /// all data has been fed, `close()` must be called for the remaining
+    /// tokens (e.g. a trailing number) to be yielded.
+    ///
+    /// Note that resuming iteration after `StopIteration` goes against
+    /// Python's iterator protocol, which requires an exhausted iterator to
+    /// stay exhausted. Code that relies on the protocol (e.g. iterators
+    /// wrapping the tokenizer, like `itertools.chain()`, or `json-stream`
+    /// itself) should therefore only be used on the tokenizer once all data
+    /// has been fed and `close()` has been called. Calling `next()` on the
+    /// tokenizer directly or iterating over it in a `for` loop is fine.
+    ///
+    /// Takes the same keyword arguments as the constructor, except for the
+    /// ones concerning the stream.
+    #[classmethod]
+    #[pyo3(signature = (**options))]
+    fn incremental(
+        _cls: &Bound<'_, PyType>,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let options = TokenizerOptions::from_kwargs(options)?;
+        let feed_buffer = SharedFeedBuffer::default();
+        let mut tokenizer = RustTokenizer::with_suitable_stream(
+            Box::new(FeedStream::new(feed_buffer.clone())),
+            options,
+        );
+        tokenizer.feed_buffer = Some(feed_buffer);
+        Ok(tokenizer)
+    }
+    /// Create a tokenizer that reads JSON from a `str` object.
+    ///
+    /// Chars are read directly from the string's internal storage, so no
+    /// intermediate copies are made (except on Python implementations that
+    /// don't expose it). Passing a `str` to the constructor has the same
+    /// effect.
+    ///
+    /// Takes the same keyword arguments as the constructor, except for the
+    /// ones concerning the stream.
+    #[classmethod]
+    #[pyo3(signature = (s, **options))]
+    fn from_str(
+        _cls: &Bound<'_, PyType>,
+        s: &Bound<'_, PyString>,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let options = TokenizerOptions::from_kwargs(options)?;
+        let mut tokenizer =
+            RustTokenizer::with_suitable_stream(Box::new(PyStrStream::new(s)?), options);
+        tokenizer.reads_without_gil = true;
+        Ok(tokenizer)
+    }
+    /// Create a tokenizer that reads JSON from the file at the given path.
+    ///
+    /// The file is memory-mapped and tokenized in place, without any reads
+    /// through Python. It must be encoded in UTF-8 and must not be modified
+    /// while the tokenizer exists, as doing so can lead to crashes. The
+    /// remainder consists of the rest of the file after the last processed
+    /// character, whose offset in the file is available as `file_offset`.
+    /// There is no stream cursor involved, so `park_cursor()` has no effect.
+    ///
+    /// Takes the same keyword arguments as the constructor, except for the
+    /// ones concerning the stream.
+    #[classmethod]
+    #[pyo3(signature = (path, **options))]
+    fn from_path(
+        _cls: &Bound<'_, PyType>,
+        path: PathBuf,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let options = TokenizerOptions::from_kwargs(options)?;
+        let stream = MmapStream::open(&path)?;
+        let file_size = stream.len();
+        let mut tokenizer = RustTokenizer::with_suitable_stream(Box::new(stream), options);
+        tokenizer.file_size = Some(file_size);
+        tokenizer.reads_without_gil = true;
+        Ok(tokenizer)
+    }
+    /// Feed data (str or bytes) to a tokenizer created via `incremental()`.
+    ///
+    /// All data fed to the same tokenizer must be of the same type. Bytes
+    /// must be valid UTF-8, but multi-byte characters may be split across
+    /// several calls.
+    #[pyo3(text_signature = "($self, data)")]
+    fn feed(slf: PyRef<'_, Self>, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        let Some(feed_buffer) = &slf.feed_buffer else {
+            return Err(PyValueError::new_err(
+                "feed() is only supported by tokenizers created via incremental()",
+            ));
+        };
+        let Some(data) = FeedData::from_py(data)? else {
+            return Err(PyTypeError::new_err(format!(
+                "can only feed str or bytes, not {}",
+                data.get_type()
+            )));
+        };
+        lock_feed_buffer(feed_buffer)
+            .feed(data)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+    /// Signal that all data has been fed to a tokenizer created via `incremental()`.
+    #[pyo3(text_signature = "($self)")]
+    fn close(slf: PyRef<'_, Self>) -> PyResult<()> {
+        let Some(feed_buffer) = &slf.feed_buffer else {
+            return Err(PyValueError::new_err(
+                "close() is only supported by tokenizers created via incremental()",
+            ));
+        };
+        lock_feed_buffer(feed_buffer)
+            .close()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
     }
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
         slf
@@ -212,53 +589,58 @@ impl RustTokenizer {
         py: Python<'_>,
     ) -> PyResult<Option<(TokenType, Option<Py<PyAny>>)>> {
         let mut now_token;
+        slf.partial = false;
         loop {
-            if slf.advance {
-                match slf.stream.read_char() {
-                    Ok(r) => match r {
-                        Some(r) => slf.c = Some(r),
-                        None => slf.c = None,
-                    },
-                    Err(e) => {
-                        let index = slf.index;
-                        return Err(PyIOError::new_err(format!(
-                            "I/O error while parsing (index {index}): {e:?}"
-                        )));
-                    }
-                }
-                slf.index += 1;
+            let max_chars = slf.reads_without_gil.then_some(MAX_CHARS_SCANNED_WITH_GIL);
+            let mut scanned = RustTokenizer::scan_chars(slf.borrow_mut(), max_chars)?;
+            if let Scanned::MaxChars = scanned {
+                // long token => worth letting other threads run in the meantime
+                let tokenizer: &mut Self = slf.borrow_mut();
+                scanned = py.detach(move || RustTokenizer::scan_chars(tokenizer, None))?;
             }
-            match slf.c {
-                Some(c) => {
-                    match RustTokenizer::process_char_py(slf.borrow_mut(), py, Char(c)) {
+            match scanned {
+                Scanned::Processed(result) => {
+                    match RustTokenizer::process_result_py(py, result) {
                         Ok(tok) => {
                             now_token = tok;
                             slf.state = slf.next_state.clone();
                         }
-                        Err(e) => {
-                            let index = slf.index;
-                            return Err(PyValueError::new_err(format!("{e} at index {index}")));
-                        }
+                        Err(e) => match RustTokenizer::recover_or_raise(slf.borrow_mut(), py, e)? {
+                            Some(error_token) => return Ok(Some(error_token)),
+                            None => continue,
+                        },
                     }
+                    RustTokenizer::emit_warning(slf.borrow_mut(), py)?;
                     if slf.completed {
                         slf.completed = false;
                         slf.token = String::new();
                         return Ok(now_token.clone());
                     }
                 }
-                None => {
-                    slf.advance = false;
-                    break;
-                }
+                // suspend until more data is available
+                Scanned::NoData => return Ok(None),
+                Scanned::Eof => break,
+                Scanned::MaxChars => unreachable!("scanning without a limit"),
             }
         }
-        match RustTokenizer::process_char_py(slf.borrow_mut(), py, Eof) {
-            Ok(tok) => {
-                now_token = tok;
+        loop {
+            match RustTokenizer::process_char_py(slf.borrow_mut(), py, Eof) {
+                Ok(tok) => {
+                    now_token = tok;
+                }
+                Err(e) => match RustTokenizer::recover_or_raise(slf.borrow_mut(), py, e)? {
+                    Some(error_token) => return Ok(Some(error_token)),
+                    None => continue,
+                },
             }
-            Err(e) => {
-                let index = slf.index;
-                return Err(PyValueError::new_err(format!("{e} at index {index}")));
+            let done = slf.completed || slf.advance;
+            if !done {
+                // EOF has to be processed again in the next state (e.g. after a string)
+                slf.state = slf.next_state.clone();
+            }
+            RustTokenizer::emit_warning(slf.borrow_mut(), py)?;
+            if done {
+                break;
             }
         }
         if slf.completed {
@@ -290,7 +672,7 @@ impl RustTokenizer {
     /// it without skipping anything.
     #[pyo3(text_signature = "($self)")]
     fn park_cursor(mut slf: PyRefMut<'_, Self>) -> PyResult<()> {
-        if let Err(e) = slf.stream.park_cursor() {
+        if let Err(e) = RustTokenizer::park_stream_cursor(&mut slf) {
             return Err(PyValueError::new_err(format!(
                 "error rewinding stream to undo readahead: {e}"
             )));
@@ -308,19 +690,435 @@ impl RustTokenizer {
     /// the buffer) even after park_cursor() has been called, so this feature
     /// allows users to write their own workarounds by obtaining the
     /// read-ahead data.
+    ///
+    /// In scan mode, it also includes chars that are to be processed again
+    /// after an invalid candidate value, unless park_cursor() could move the
+    /// stream's cursor back to before them.
     #[getter]
     fn remainder(slf: PyRefMut<'_, Self>) -> StreamData {
-        slf.stream.remainder()
+        RustTokenizer::unprocessed_data(&slf)
+    }
+    /// Hand the data that hasn't been processed yet over to a file-like object.
+    ///
+    /// Reading from the returned object first yields the data that has been
+    /// read from the underlying stream but not processed yet (see
+    /// `remainder`) and then the data that follows it in the stream, so
+    /// other code can continue reading where the tokenizer left off without
+    /// losing any read-ahead data, whatever the buffering mode. For
+    /// iterables of chunks, the remaining chunks are read from the same
+    /// iterator.
+    ///
+    /// The tokenizer behaves as if it had reached the end of the stream
+    /// afterwards. Not supported for compressed streams.
+    #[pyo3(text_signature = "($self)")]
+    fn into_stream(mut slf: PyRefMut<'_, Self>) -> PyResult<ContinuationStream> {
+        if slf.compression_offsets.is_some() {
+            return Err(PyValueError::new_err(
+                "into_stream() is not supported for compressed streams",
+            ));
+        }
+        // for streams that support it, this minimizes the remainder (or even gets rid of it)
+        if let Err(e) = RustTokenizer::park_stream_cursor(&mut slf) {
+            return Err(PyValueError::new_err(format!(
+                "error rewinding stream to undo readahead: {e}"
+            )));
+        }
+        let remainder = RustTokenizer::unprocessed_data(&slf);
+        slf.replay.clear();
+        let source = slf.source.take();
+        if let Some(feed_buffer) = &slf.feed_buffer {
+            // so that feeding more data is an error instead of being silently ignored
+            *lock_feed_buffer(feed_buffer) = FeedBuffer::consumed();
+        }
+        slf.stream = Box::new(FeedStream::exhausted());
+        Ok(ContinuationStream::new(remainder, source))
+    }
+    /// Reset the tokenizer's state to start tokenizing a new document.
+    ///
+    /// Any partially tokenized token is discarded, but data that has already
+    /// been read from the underlying stream into the internal buffer is kept,
+    /// so this can be used to process streams containing several
+    /// concatenated documents (e.g. after an invalid document caused an
+    /// exception) without losing read-ahead data the way creating a new
+    /// tokenizer for the same stream would. In record modes, the current
+    /// record number is kept.
+    #[pyo3(text_signature = "($self)")]
+    fn reset(mut slf: PyRefMut<'_, Self>) {
+        RustTokenizer::discard_partial_token(&mut slf);
+        // (the record number is kept, as it reflects the position in the stream)
+        slf.state = State::Whitespace;
+        slf.next_state = State::Whitespace;
+        slf.containers.clear();
+        slf.document_open = false;
+        slf.record_value_done = false;
+        slf.warning = None;
+        slf.partial = false;
+        slf.scan_start = None;
+        // (chars pending replay haven't been processed yet, so like buffered data, they're kept)
+        slf.scan_history.clear();
+        slf.generation += 1;
+    }
+    /// Number of compressed bytes read from the stream so far.
+    ///
+    /// None if the tokenizer wasn't created with `compression` set. Includes
+    /// data that has been read ahead.
+    #[getter]
+    fn compressed_offset(slf: PyRef<'_, Self>) -> Option<u64> {
+        slf.compression_offsets.as_ref().map(|o| o.compressed())
+    }
+    /// Offset in the decompressed data up to which it has been processed.
+    ///
+    /// None if the tokenizer wasn't created with `compression` set.
+    #[getter]
+    fn decompressed_offset(slf: PyRef<'_, Self>) -> Option<u64> {
+        let offsets = slf.compression_offsets.as_ref()?;
+        let n_bytes_read_ahead = match RustTokenizer::unprocessed_data(&slf) {
+            StreamData::Bytes(b) => b.len(),
+            StreamData::Text(s) => s.len(),
+        };
+        Some(offsets.decompressed() - n_bytes_read_ahead as u64)
+    }
+    /// Offset in the file up to which it has been processed.
+    ///
+    /// None if the tokenizer wasn't created via `from_path()`. Opening the
+    /// file and seeking to this offset continues reading where the tokenizer
+    /// left off, i.e. it yields the same data as `remainder`.
+    #[getter]
+    fn file_offset(slf: PyRef<'_, Self>) -> Option<u64> {
+        let file_size = slf.file_size?;
+        let n_bytes_unprocessed = match RustTokenizer::unprocessed_data(&slf) {
+            StreamData::Bytes(b) => b.len(),
+            StreamData::Text(s) => s.len(),
+        };
+        Some(file_size - n_bytes_unprocessed as u64)
+    }
+    /// Whether the last emitted token was cut off by the end of the stream.
+    ///
+    /// Only ever true if the tokenizer was created with `allow_partial=True`.
+    #[getter]
+    fn partial(slf: PyRef<'_, Self>) -> bool {
+        slf.partial
+    }
+    /// Objects and arrays that are currently open, from outermost to innermost.
+    ///
+    /// Returned as a list of their opening brackets (`"{"` or `"["`), so
+    /// closing brackets for the remaining containers can be obtained by
+    /// reversing it. Together with `allow_partial=True`, this allows
+    /// completing truncated JSON.
+    #[pyo3(text_signature = "($self)")]
+    fn open_containers(slf: PyRef<'_, Self>) -> Vec<String> {
+        slf.containers.iter().map(|c| c.to_string()).collect()
+    }
+    /// Errors that were recovered from so far, as a list of `Diagnostic`s.
+    ///
+    /// Only populated if the tokenizer was created with `recover=True`.
+    #[getter]
+    fn errors(slf: PyRef<'_, Self>, py: Python<'_>) -> Vec<Py<Diagnostic>> {
+        slf.errors.iter().map(|e| e.clone_ref(py)).collect()
+    }
+    /// Iterate over the documents in a multi-document stream.
+    ///
+    /// Useful for streams made up of several concatenated JSON documents,
+    /// optionally separated by whitespace (which includes newline-delimited
+    /// JSON). Each item is itself an iterator over the tokens of one
+    /// document. Tokens of a document that haven't been consumed by the time
+    /// the next document is requested are skipped.
+    #[pyo3(text_signature = "($self)")]
+    fn documents(slf: Py<Self>) -> DocumentIterator {
+        DocumentIterator::new(slf)
     }
 }
 
 impl RustTokenizer {
-    fn process_char_py(
+    fn with_suitable_stream(
+        stream: Box<dyn SuitableStream + Send + Sync>,
+        options: TokenizerOptions,
+    ) -> Self {
+        RustTokenizer {
+            stream,
+            completed: false,
+            advance: true,
+            token: String::new(),
+            state: State::Whitespace,
+            next_state: State::Whitespace,
+            index: -1,
+            c: None,
+            unicode_buffer: CompactString::with_capacity(4),
+            prev_charcode: None,
+            containers: Vec::new(),
+            document_open: false,
+            generation: 0,
+            record_format: options.record_format,
+            record: 0,
+            record_value_done: false,
+            pending_token: None,
+            warning: None,
+            recover: options.recover,
+            emit_error_tokens: options.emit_error_tokens,
+            errors: Vec::new(),
+            scan: options.scan,
+            scan_start: None,
+            scan_history: Vec::new(),
+            replay: VecDeque::new(),
+            allow_partial: options.allow_partial,
+            partial: false,
+            feed_buffer: None,
+            compression_offsets: None,
+            file_size: None,
+            source: None,
+            reads_without_gil: false,
+        }
+    }
+
+    /// Park the stream's cursor before the chars that are still to be replayed, if possible.
+    ///
+    /// If the stream can put them back, they're read from it again, otherwise they remain to be
+    /// replayed and are part of the remainder (see `unprocessed_data`).
+    fn park_stream_cursor(slf: &mut Self) -> io::Result<()> {
+        let unread = slf.replay.iter().collect::<String>();
+        if slf.stream.park_cursor_before(&unread)? {
+            slf.replay.clear();
+        }
+        Ok(())
+    }
+
+    /// The stream's remainder preceded by the chars that are still to be replayed.
+    fn unprocessed_data(slf: &Self) -> StreamData {
+        let replay = slf.replay.iter().collect::<String>();
+        match slf.stream.remainder() {
+            StreamData::Text(s) => StreamData::Text(replay + &s),
+            StreamData::Bytes(b) => StreamData::Bytes([replay.into_bytes(), b].concat()),
+        }
+    }
+
+    /// Read the next char into `c`.
+    ///
+    /// Returns false if no data is available yet in an incremental tokenizer, in which case
+    /// nothing is changed. For other tokenizers, the same situation (which can only occur for
+    /// non-blocking streams) results in a `BlockingIOError`.
+    fn read_next_char(slf: &mut Self) -> PyResult<bool> {
+        if let Some(c) = slf.replay.pop_front() {
+            slf.c = Some(c);
+        } else {
+            match slf.stream.read_char() {
+                Ok(r) => slf.c = r,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    if slf.feed_buffer.is_some() {
+                        return Ok(false);
+                    }
+                    return Err(PyBlockingIOError::new_err(
+                        "no data available from non-blocking stream yet",
+                    ));
+                }
+                Err(e) => {
+                    let index = slf.index;
+                    let message = format!("I/O error while parsing (index {index}): {e:?}");
+                    // Python exceptions raised while reading (e.g. KeyboardInterrupt while
+                    // waiting for more data in follow mode) are passed through as they are
+                    return Err(
+                        match e.into_inner().map(|inner| inner.downcast::<PyErr>()) {
+                            Some(Ok(py_err)) => *py_err,
+                            _ => PyIOError::new_err(message),
+                        },
+                    );
+                }
+            }
+        }
+        if let (Some(_), Some(c)) = (slf.scan_start, slf.c) {
+            slf.scan_history.push(c);
+        }
+        slf.index += 1;
+        Ok(true)
+    }
+
+    /// Skip whitespace between documents.
+    ///
+    /// Returns whether there is anything other than whitespace left in the
+    /// stream. The first non-whitespace char is left for the next call of
+    /// `__next__` to process.
+    fn skip_whitespace(slf: &mut Self) -> PyResult<bool> {
+        loop {
+            if slf.advance && !RustTokenizer::read_next_char(slf)? {
+                return Ok(false);
+            }
+            match slf.c {
+                Some(c)
+                    if (c.is_whitespace()
+                        || (c == RECORD_SEPARATOR
+                            && slf.record_format == Some(RecordFormat::JsonSeq))
+                        || (slf.scan && !matches!(c, '{' | '[')))
+                        && matches!(slf.state, State::Whitespace | State::StringEnd) =>
+                {
+                    if c == '\n' && slf.record_format == Some(RecordFormat::Ndjson) {
+                        // blank line (a non-blank one would still be part of the previous document)
+                        slf.record += 1;
+                    }
+                    slf.state = State::Whitespace;
+                    slf.advance = true;
+                }
+                Some(_) => {
+                    slf.advance = false;
+                    return Ok(true);
+                }
+                None => {
+                    slf.advance = false;
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    /// Recover from a parsing error if the mode we're in allows it, or turn it into a Python
+    /// exception otherwise.
+    ///
+    /// Returns the error token to emit, if any.
+    fn recover_or_raise(
+        slf: &mut Self,
+        py: Python<'_>,
+        e: ParsingError,
+    ) -> PyResult<Option<(TokenType, Option<Py<PyAny>>)>> {
+        let index = slf.index;
+        if let (true, ParsingError::InvalidJson(message)) = (slf.scan_start.is_some(), &e) {
+            return RustTokenizer::backtrack(slf, py, message.clone()).map(Some);
+        }
+        let message = match e {
+            ParsingError::InvalidJson(message) if slf.record_format.is_some() || slf.recover => {
+                message
+            }
+            e => return Err(PyValueError::new_err(format!("{e} at index {index}"))),
+        };
+        let diagnostic = Py::new(
+            py,
+            Diagnostic {
+                message,
+                index,
+                record: slf.record_format.map(|_| slf.record),
+            },
+        )?;
+        if slf.recover {
+            slf.errors.push(diagnostic.clone_ref(py));
+        }
+        if slf.record_format.is_some() {
+            RustTokenizer::skip_record(slf);
+        } else {
+            RustTokenizer::skip_to_delimiter(slf);
+            if !slf.emit_error_tokens {
+                return Ok(None);
+            }
+        }
+        Ok(Some((TokenType::Error_, Some(diagnostic.into_any()))))
+    }
+
+    /// Abandon the current candidate value in scan mode and continue scanning right after its
+    /// start.
+    ///
+    /// Returns the error token to emit.
+    fn backtrack(
         slf: &mut Self,
         py: Python<'_>,
-        c: CharOrEof,
-    ) -> Result<Option<(TokenType, Option<Py<PyAny>>)>, ParsingError> {
-        match RustTokenizer::process_char(slf.borrow_mut(), c) {
+        message: String,
+    ) -> PyResult<(TokenType, Option<Py<PyAny>>)> {
+        let diagnostic = Py::new(
+            py,
+            Diagnostic {
+                message,
+                index: slf.index,
+                record: None,
+            },
+        )?;
+        if slf.recover {
+            slf.errors.push(diagnostic.clone_ref(py));
+        }
+        let history = std::mem::take(&mut slf.scan_history);
+        // the first char is the opening bracket of the failed candidate
+        for c in history.into_iter().skip(1).rev() {
+            slf.replay.push_front(c);
+        }
+        if let Some(start) = slf.scan_start.take() {
+            slf.index = start;
+        }
+        RustTokenizer::discard_partial_token(slf);
+        slf.state = State::Whitespace;
+        slf.next_state = State::Whitespace;
+        slf.containers.clear();
+        slf.document_open = false;
+        slf.advance = true;
+        Ok((TokenType::Error_, Some(diagnostic.into_any())))
+    }
+
+    /// Discard the token that is currently being tokenized, if any.
+    fn discard_partial_token(slf: &mut Self) {
+        slf.completed = false;
+        slf.token.clear();
+        slf.unicode_buffer.clear();
+        slf.prev_charcode = None;
+        slf.pending_token = None;
+    }
+
+    /// Discard the current token and skip to the next structural character.
+    fn skip_to_delimiter(slf: &mut Self) {
+        RustTokenizer::discard_partial_token(slf);
+        slf.state = State::SkipToDelimiter;
+        slf.next_state = State::SkipToDelimiter;
+        // the char that caused the error might be a structural character itself
+        slf.advance = false;
+    }
+
+    /// Discard the current record and skip to the start of the next one.
+    fn skip_record(slf: &mut Self) {
+        RustTokenizer::discard_partial_token(slf);
+        slf.containers.clear();
+        slf.document_open = false;
+        slf.record_value_done = false;
+        if slf.record_format == Some(RecordFormat::JsonSeq) {
+            // separators only advance the record number after records with a value
+            slf.record += 1;
+        }
+        slf.state = State::SkipRecord;
+        slf.next_state = State::SkipRecord;
+        // the char that caused the error might be the separator ending the record
+        slf.advance = false;
+    }
+
+    /// Read and process chars until one of them needs Python to be involved.
+    ///
+    /// That is the case if it completes a token, causes an error or warning, or if there are no
+    /// chars left (for now). Doesn't touch any Python objects itself, so it can run without
+    /// holding the GIL (although the stream may acquire it to read more data). If `max_chars` is
+    /// given, stops after processing that many chars regardless.
+    fn scan_chars(slf: &mut Self, max_chars: Option<usize>) -> PyResult<Scanned> {
+        let mut n_chars = 0;
+        loop {
+            if max_chars.is_some_and(|max_chars| n_chars >= max_chars) {
+                return Ok(Scanned::MaxChars);
+            }
+            if slf.advance && !RustTokenizer::read_next_char(slf)? {
+                return Ok(Scanned::NoData);
+            }
+            let Some(c) = slf.c else {
+                slf.advance = false;
+                return Ok(Scanned::Eof);
+            };
+            n_chars += 1;
+            let result = RustTokenizer::process_char(slf, Char(c));
+            if result.is_err() || slf.completed || slf.warning.is_some() {
+                return Ok(Scanned::Processed(result));
+            }
+            slf.state = slf.next_state.clone();
+        }
+    }
+
+    fn process_char_py(slf: &mut Self, py: Python<'_>, c: CharOrEof) -> PyTokenResult {
+        let result = RustTokenizer::process_char(slf.borrow_mut(), c);
+        RustTokenizer::process_result_py(py, result)
+    }
+
+    /// Convert the result of processing a char to Python.
+    fn process_result_py(
+        py: Python<'_>,
+        result: Result<Option<Token>, ParsingError>,
+    ) -> PyTokenResult {
+        match result {
             Ok(Some(Token::Operator(s))) => Ok(Some((
                 TokenType::Operator,
                 Some(s.into_pyobject(py).unwrap_infallible().unbind().into_any()),
@@ -350,11 +1148,97 @@ impl RustTokenizer {
                 ),
             ))),
             Ok(Some(Token::Null)) => Ok(Some((TokenType::Null, None))),
+            Ok(Some(Token::ValueStart(index))) => Ok(Some((
+                TokenType::ValueStart,
+                Some(
+                    index
+                        .into_pyobject(py)
+                        .unwrap_infallible()
+                        .unbind()
+                        .into_any(),
+                ),
+            ))),
+            Ok(Some(Token::RecordEnd(record))) => Ok(Some((
+                TokenType::RecordEnd,
+                Some(
+                    record
+                        .into_pyobject(py)
+                        .unwrap_infallible()
+                        .unbind()
+                        .into_any(),
+                ),
+            ))),
             Ok(None) => Ok(None),
             Err(e) => Err(e),
         }
     }
 
+    /// Emit the warning that processing the last char resulted in, if any.
+    ///
+    /// Must only be called once the tokenizer is in a consistent state again, as warnings can be
+    /// turned into exceptions, which are passed through as they are.
+    fn emit_warning(slf: &mut Self, py: Python<'_>) -> PyResult<()> {
+        let Some(warning) = slf.warning.take() else {
+            return Ok(());
+        };
+        let message = CString::new(warning).map_err(|e| {
+            PyValueError::new_err(
+                ParsingError::BugError(format!("warning message contains null byte: {e}"))
+                    .to_string(),
+            )
+        })?;
+        PyErr::warn(py, &py.get_type::<TruncatedValueWarning>(), &message, 1)
+    }
+
+    /// Best-effort token for the string, number or literal cut off by the end of the stream.
+    ///
+    /// Returns `None` if there is nothing to salvage (e.g. a lone minus sign).
+    fn partial_token(slf: &Self) -> Result<Option<Token>, ParsingError> {
+        Ok(match slf.state {
+            State::String_
+            | State::StringEscape
+            | State::Unicode
+            | State::UnicodeSurrogateStart
+            | State::UnicodeSurrogateStringEscape
+            | State::UnicodeSurrogate => Some(Token::String_(slf.token.clone())),
+            State::Integer0
+            | State::IntegerSign
+            | State::Integer
+            | State::IntegerExp0
+            | State::IntegerExp
+            | State::FloatingPoint0
+            | State::FloatingPoint => {
+                // drop trailing chars that can't end a number (e.g. "1." or "1e-")
+                let number = slf.token.trim_end_matches(['.', 'e', 'E', '+', '-']);
+                if number.is_empty() {
+                    None
+                } else if number.contains(['.', 'e', 'E']) {
+                    Some(Token::Float(number.parse::<f64>()?))
+                } else {
+                    Some(Token::Integer(parse_integer(number)?))
+                }
+            }
+            State::True1 | State::True2 | State::True3 => Some(Token::Boolean(true)),
+            State::False1 | State::False2 | State::False3 | State::False4 => {
+                Some(Token::Boolean(false))
+            }
+            State::Null1 | State::Null2 | State::Null3 => Some(Token::Null),
+            State::NaN1 | State::NaN2 => Some(Token::Float(f64::NAN)),
+            State::Inf1
+            | State::Inf2
+            | State::Inf3
+            | State::Inf4
+            | State::Inf5
+            | State::Inf6
+            | State::Inf7 => Some(Token::Float(if slf.token == "-" {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            })),
+            _ => None,
+        })
+    }
+
     fn process_char(slf: &mut Self, c: CharOrEof) -> Result<Option<Token>, ParsingError> {
         slf.advance = true;
         slf.next_state = slf.state.clone();
@@ -362,22 +1246,125 @@ impl RustTokenizer {
         let mut add_char = false;
         let mut c = c;
 
+        if slf.scan && slf.containers.is_empty() && matches!(slf.state, State::Whitespace) {
+            match c {
+                Char(c_ @ ('{' | '[')) if slf.scan_start.is_none() => {
+                    // announce the candidate value, then process its opening bracket as usual
+                    slf.scan_start = Some(slf.index);
+                    slf.scan_history.push(c_);
+                    slf.completed = true;
+                    slf.document_open = true;
+                    slf.advance = false;
+                    return Ok(Some(Token::ValueStart(slf.index)));
+                }
+                Char('{' | '[') | Eof => (),
+                Char(_) => {
+                    // not part of any JSON value
+                    return Ok(None);
+                }
+            }
+        }
+
+        if slf.scan
+            && !slf.containers.is_empty()
+            && matches!(slf.state, State::Whitespace)
+            && matches!(c, Eof)
+        {
+            return Err(ParsingError::InvalidJson(
+                "Unexpected end of file inside value".to_string(),
+            ));
+        }
+
+        if let Some(record_format) = slf.record_format {
+            let separator = record_format.separator();
+            let separator_name = record_format.separator_name();
+            let is_separator = matches!(c, Char(c_) if c_ == separator);
+            match slf.state {
+                State::Whitespace if slf.record_value_done => {
+                    if let Char(c_) = c {
+                        if !c_.is_whitespace() && c_ != separator {
+                            return Err(ParsingError::InvalidJson(format!(
+                                "Expected {separator_name} after record value.  Got {c:?}"
+                            )));
+                        }
+                    }
+                }
+                State::String_ | State::Unicode | State::UnicodeSurrogate if is_separator => {
+                    return Err(ParsingError::InvalidJson(format!(
+                        "Unexpected {separator_name} inside string"
+                    )));
+                }
+                _ => (),
+            }
+        }
+
+        if slf.record_format == Some(RecordFormat::JsonSeq)
+            && !slf.containers.is_empty()
+            && matches!(c, Char(RECORD_SEPARATOR))
+            && slf.state.is_scalar()
+        {
+            return Err(ParsingError::InvalidJson(
+                "Unexpected record separator inside record".to_string(),
+            ));
+        }
+
+        if slf.record_format == Some(RecordFormat::JsonSeq)
+            && slf.containers.is_empty()
+            && matches!(c, Char(RECORD_SEPARATOR) | Eof)
+            && slf.state.is_scalar()
+        {
+            // RFC 7464 section 2.4: top-level numbers and literals must be followed by
+            // whitespace, otherwise they might have been truncated
+            slf.warning = Some(format!(
+                "Discarding possibly truncated top-level value in record {} (index {})",
+                slf.record, slf.index
+            ));
+            slf.token.clear();
+            slf.pending_token = None;
+            slf.record += 1;
+            slf.next_state = State::Whitespace;
+            slf.advance = false;
+            return Ok(None);
+        }
+
+        let cut_off = slf.allow_partial
+            && matches!(c, Eof)
+            && !matches!(
+                slf.state,
+                State::Whitespace
+                    | State::StringEnd
+                    | State::TopLevelLiteralEnd
+                    | State::SkipRecord
+                    | State::SkipToDelimiter
+            );
+
         match slf.state {
+            _ if cut_off => {
+                now_token = RustTokenizer::partial_token(slf)?;
+                slf.completed = now_token.is_some();
+                slf.partial = now_token.is_some();
+                slf.next_state = State::Whitespace;
+                slf.advance = false;
+            }
             State::Whitespace => match c {
                 Char('{') => {
                     slf.completed = true;
+                    slf.containers.push('{');
                     now_token = Some(Token::Operator("{".to_owned()));
                 }
                 Char('}') => {
                     slf.completed = true;
+                    slf.containers.pop();
                     now_token = Some(Token::Operator("}".to_owned()));
                 }
                 Char('[') => {
                     slf.completed = true;
+                    slf.containers.push('[');
                     now_token = Some(Token::Operator("[".to_owned()));
                 }
                 Char(']') => {
                     slf.completed = true;
+                    slf.containers.pop();
                     now_token = Some(Token::Operator("]".to_owned()));
                 }
                 Char(',') => {
@@ -418,6 +1405,22 @@ impl RustTokenizer {
                 Char('I') => {
                     slf.next_state = State::Inf1;
                 }
+                Char(c_) if Some(c_) == slf.record_format.map(RecordFormat::separator) => {
+                    if !slf.containers.is_empty() {
+                        return Err(ParsingError::InvalidJson(format!(
+                            "Unexpected {} inside record",
+                            slf.record_format.map_or("", RecordFormat::separator_name)
+                        )));
+                    }
+                    if slf.record_value_done {
+                        slf.completed = true;
+                        now_token = Some(Token::RecordEnd(slf.record));
+                        slf.record += 1;
+                    } else if slf.record_format == Some(RecordFormat::Ndjson) {
+                        // blank line
+                        slf.record += 1;
+                    }
+                }
                 Char(c_) => {
                     if !c_.is_whitespace() {
                         return Err(ParsingError::InvalidJson(format!(
@@ -425,6 +1428,17 @@ impl RustTokenizer {
                         )));
                     }
                 }
+                Eof if slf.record_format.is_some() => {
+                    if !slf.containers.is_empty() {
+                        return Err(ParsingError::InvalidJson(
+                            "Unexpected end of file inside record".to_string(),
+                        ));
+                    }
+                    if slf.record_value_done {
+                        slf.completed = true;
+                        now_token = Some(Token::RecordEnd(slf.record));
+                    }
+                }
                 Eof => (),
             },
             State::Integer => match c {
@@ -442,22 +1456,7 @@ impl RustTokenizer {
                 _ if is_delimiter(c) => {
                     slf.next_state = State::Whitespace;
                     slf.completed = true;
-                    match AppropriateInt::from_str(&slf.token) {
-                        Ok(parsed_num) => {
-                            now_token = Some(Token::Integer(parsed_num));
-                        }
-                        Err(ParseIntError::General(e)) => {
-                            return Err(ParsingError::InvalidJson(format!(
-                                "Could not parse integer: {e}"
-                            )));
-                        }
-                        Err(ParseIntError::TooLargeOrSmall) => {
-                            return Err(ParsingError::Limitation(
-                                "Incapable of parsing integer due to platform constraint"
-                                    .to_string(),
-                            ));
-                        }
-                    }
+                    now_token = Some(Token::Integer(parse_integer(&slf.token)?));
                     slf.advance = false;
                 }
                 _ => {
@@ -791,7 +1790,11 @@ impl RustTokenizer {
                 }
             },
             State::StringEnd => {
-                if is_delimiter(c) {
+                let is_separator = matches!(
+                    c,
+                    Char(c_) if Some(c_) == slf.record_format.map(RecordFormat::separator)
+                );
+                if is_delimiter(c) || is_separator {
                     slf.advance = false;
                     slf.next_state = State::Whitespace;
                 } else {
@@ -948,6 +1951,32 @@ impl RustTokenizer {
                     add_char = true;
                 }
             }
+            State::TopLevelLiteralEnd => {
+                if is_delimiter(c) {
+                    slf.completed = true;
+                    now_token = slf.pending_token.take();
+                    slf.next_state = State::Whitespace;
+                    slf.advance = false;
+                } else {
+                    return Err(ParsingError::InvalidJson(format!(
+                        "Expected whitespace | an operator after literal.  Got {c:?}"
+                    )));
+                }
+            }
+            State::SkipToDelimiter => {
+                if matches!(c, Char('{' | '}' | '[' | ']' | ':' | ',') | Eof) {
+                    slf.next_state = State::Whitespace;
+                    slf.advance = false;
+                }
+            }
+            State::SkipRecord => {
+                let separator = slf.record_format.map(RecordFormat::separator);
+                if matches!(c, Char(c_) if Some(c_) == separator) {
+                    // let the separator be processed as usual to end the record
+                    slf.next_state = State::Whitespace;
+                    slf.advance = false;
+                }
+            }
         }
 
         if add_char {
@@ -956,6 +1985,36 @@ impl RustTokenizer {
             }
         };
 
+        if slf.completed
+            && slf.record_format == Some(RecordFormat::JsonSeq)
+            && slf.containers.is_empty()
+            && matches!(
+                slf.state,
+                State::True3 | State::False4 | State::Null3 | State::NaN2 | State::Inf7
+            )
+        {
+            // hold back top-level literals until we know they haven't been truncated
+            slf.completed = false;
+            slf.pending_token = now_token.take();
+            slf.next_state = State::TopLevelLiteralEnd;
+        }
+
+        if slf.completed {
+            let is_record_end = matches!(now_token, Some(Token::RecordEnd(_)));
+            slf.document_open = if slf.record_format.is_some() {
+                // in record modes, documents end with the end of their record
+                !is_record_end
+            } else {
+                !slf.containers.is_empty()
+            };
+            slf.record_value_done = slf.containers.is_empty() && !is_record_end;
+            if slf.containers.is_empty() {
+                // candidate value is complete
+                slf.scan_start = None;
+                slf.scan_history.clear();
+            }
+        }
+
         Ok(now_token)
     }
 }
@@ -969,9 +2028,17 @@ fn supports_bigint() -> PyResult<bool> {
     Ok(int::supports_bigint())
 }
 
-#[pymodule]
+// all state shared between threads is either behind PyO3's borrow checking (pyclasses) or in
+// thread-safe containers, so the GIL isn't needed to protect any of it
+#[pymodule(gil_used = false)]
 fn json_stream_rs_tokenizer(_py: Python<'_>, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<RustTokenizer>()?;
+    m.add_class::<Diagnostic>()?;
+    m.add_class::<ContinuationStream>()?;
+    m.add(
+        "TruncatedValueWarning",
+        m.py().get_type::<TruncatedValueWarning>(),
+    )?;
     m.add_wrapped(wrap_pyfunction!(supports_bigint))?;
 
     Ok(())
@@ -0,0 +1,55 @@
+/// Char source for memory-mapped files.
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use crate::utf8_reader::read_char_from_slice;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// "Suitable" stream (see `SuitableStream`) that reads data from a memory-mapped file.
+///
+/// There is no Python object involved at all, so the stream's position is simply the offset in
+/// the file up to which chars have been read: `park_cursor` is a no-op (there is no cursor to
+/// move) and the remainder consists of the rest of the file after that offset, so the offset up to
+/// which chars have been processed is the file's size minus the remainder's.
+pub struct MmapStream {
+    mmap: Mmap,
+    pos: usize,
+}
+
+impl MmapStream {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: Modifying the file while it's mapped is undefined behavior as far as Rust is
+        // concerned. There is no way to prevent this, so we have to rely on users not doing it
+        // (as documented for `from_path()`), like every other user of memory-mapped files.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(MmapStream { mmap, pos: 0 })
+    }
+
+    /// Size of the mapped file in bytes.
+    pub fn len(&self) -> u64 {
+        self.mmap.len() as u64
+    }
+}
+
+impl Utf8CharSource for MmapStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        read_char_from_slice(&self.mmap, &mut self.pos)
+    }
+}
+
+impl ParkCursorChars for MmapStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        // no-op
+        Ok(())
+    }
+}
+
+impl Remainder for MmapStream {
+    fn remainder(&self) -> StreamData {
+        StreamData::Bytes(self.mmap[self.pos..].to_vec())
+    }
+}
@@ -5,7 +5,6 @@ pub enum OpaqueSeekFrom<P> {
     Start(P),
     #[allow(dead_code)] // to be honest I don't understand why this is dead code if it's public...
     End,
-    Current,
 }
 
 /// A trait for "opaque" seeks like those encountered in Python's text IO.
@@ -21,4 +20,7 @@ pub trait OpaqueSeek {
 
     fn seek(&mut self, pos: OpaqueSeekFrom<Self::OpaqueSeekPos>)
         -> io::Result<Self::OpaqueSeekPos>;
+
+    /// Return the current position without seeking, which could discard read-ahead data.
+    fn tell(&mut self) -> io::Result<Self::OpaqueSeekPos>;
 }
@@ -10,4 +10,14 @@ use std::io;
 /// be nonsense (I haven't thought about what happens b/c I don't need it).
 pub trait ParkCursorChars: Utf8CharSource {
     fn park_cursor(&mut self) -> io::Result<()>;
+
+    /// Park the cursor before the given chars, which must be the last ones that were read.
+    ///
+    /// Returns whether that was done, in which case reading resumes with these chars. The default
+    /// implementation is for streams that can't move the cursor back and just parks it as usual,
+    /// so it's up to the caller to keep the chars around.
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        self.park_cursor()?;
+        Ok(unread.is_empty())
+    }
 }
@@ -0,0 +1,249 @@
+/// Reading ahead from file descriptors in a background thread.
+use crate::buffer_size::BufferSize;
+use crate::fd_reader::FdReader;
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use pyo3::Python;
+use std::fs::File;
+use std::io;
+use std::io::{Seek, SeekFrom};
+use std::os::unix::fs::FileExt;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+use utf8_width::get_width;
+
+/// Interval in which waiting for a chunk is interrupted to check for signals (e.g. Ctrl+C).
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Request for the background thread to read a chunk of the given size at the given position.
+type ChunkRequest = (u64, usize);
+
+/// Read chunks as requested until the requesting side goes away.
+fn serve_chunk_requests(
+    file: File,
+    requests: Receiver<ChunkRequest>,
+    chunks: Sender<io::Result<Vec<u8>>>,
+) {
+    for (pos, size) in requests {
+        let mut buf = vec![0; size];
+        let result = loop {
+            match file.read_at(&mut buf, pos) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+        let chunk = result.map(|n_bytes_read| {
+            buf.truncate(n_bytes_read);
+            buf
+        });
+        if chunks.send(chunk).is_err() {
+            break;
+        }
+    }
+}
+
+/// Handle to the background thread along with the state of the (at most one) pending request.
+struct Prefetcher {
+    requests: Sender<ChunkRequest>,
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    pending: bool,
+    // chunk that has arrived but hasn't been taken yet
+    arrived: Option<io::Result<Vec<u8>>>,
+}
+
+impl Prefetcher {
+    fn request(&mut self, request: ChunkRequest) -> io::Result<()> {
+        self.requests
+            .send(request)
+            .map_err(|_| io::Error::other("read-ahead thread has terminated"))?;
+        self.pending = true;
+        Ok(())
+    }
+
+    /// Wait for the pending request's chunk to arrive, if there is one.
+    fn wait(&mut self) -> io::Result<()> {
+        if !self.pending {
+            return Ok(());
+        }
+        let chunk = Python::attach(|py| loop {
+            // (a mutable reference can be sent to another thread, unlike a shared one, as
+            // receivers aren't Sync)
+            let chunks = &mut self.chunks;
+            match py.detach(move || chunks.recv_timeout(SIGNAL_CHECK_INTERVAL)) {
+                Ok(chunk) => return Ok(chunk),
+                Err(RecvTimeoutError::Timeout) => py.check_signals().map_err(io::Error::other)?,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::other("read-ahead thread has terminated"))
+                }
+            }
+        })?;
+        self.pending = false;
+        self.arrived = Some(chunk);
+        Ok(())
+    }
+
+    /// Take the chunk of the last request, waiting for it to arrive if necessary.
+    fn take(&mut self) -> io::Result<Vec<u8>> {
+        self.wait()?;
+        self.arrived
+            .take()
+            .unwrap_or_else(|| Err(io::Error::other("no chunk has been requested")))
+    }
+}
+
+/// "Suitable" stream (see `SuitableStream`) that reads from the file descriptor underlying a
+/// Python file object (see `FdReader`) in a background thread.
+///
+/// While the tokenizer processes one chunk, the thread already reads the next one with the GIL
+/// released, so waiting for I/O and tokenizing overlap. Only chunks that have been taken by the
+/// tokenizer count as read: The Python-visible position is set to the end of the last such chunk
+/// and parking the cursor works as usual.
+///
+/// Only seekable files are supported, as data read ahead from unseekable ones (e.g. pipes) would
+/// be gone from them without having been processed, and waiting for it to arrive in order to
+/// return it as part of the remainder could block indefinitely.
+pub struct PrefetchStream {
+    fd_reader: FdReader, // for keeping the Python-visible position up to date
+    prefetcher: Mutex<Prefetcher>,
+    buffer_size: BufferSize,
+    // current chunk (preceded by the bytes of an incomplete char from the previous one), of
+    // which the first `pos` bytes have been processed
+    buf: Vec<u8>,
+    pos: usize,
+    // file offset of the start of `buf`
+    buf_start: u64,
+}
+
+impl PrefetchStream {
+    /// Returns an error of kind `Unsupported` for unseekable files.
+    pub fn new(fd_reader: FdReader, buffer_size: BufferSize) -> io::Result<Self> {
+        let Some(buf_start) = fd_reader.pos() else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "reading ahead is only supported for seekable files",
+            ));
+        };
+        let file = fd_reader.try_clone_file()?;
+        let (request_sender, request_receiver) = channel();
+        let (chunk_sender, chunk_receiver) = channel();
+        thread::Builder::new()
+            .name("json-stream-rs-tokenizer-prefetch".to_owned())
+            .spawn(move || serve_chunk_requests(file, request_receiver, chunk_sender))?;
+        Ok(PrefetchStream {
+            fd_reader,
+            prefetcher: Mutex::new(Prefetcher {
+                requests: request_sender,
+                chunks: chunk_receiver,
+                pending: false,
+                arrived: None,
+            }),
+            buffer_size,
+            buf: Vec::new(),
+            pos: 0,
+            buf_start,
+        })
+    }
+
+    fn lock_prefetcher(&self) -> MutexGuard<'_, Prefetcher> {
+        // the prefetcher is always left in a consistent state, so poisoning doesn't matter
+        self.prefetcher
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn next_request(&mut self) -> ChunkRequest {
+        let pos = self.buf_start + self.buf.len() as u64;
+        (pos, self.buffer_size.next_size())
+    }
+
+    /// Take the next chunk, keeping the unprocessed bytes at the start of the buffer, and
+    /// request the one after it. Returns whether any new data was obtained.
+    fn next_chunk(&mut self) -> io::Result<bool> {
+        let requested = {
+            let prefetcher = self.lock_prefetcher();
+            prefetcher.pending || prefetcher.arrived.is_some()
+        };
+        if !requested {
+            let request = self.next_request();
+            self.lock_prefetcher().request(request)?;
+        }
+        let chunk = self.lock_prefetcher().take()?;
+        let got_new_data = !chunk.is_empty();
+        let unprocessed = self.buf.split_off(self.pos);
+        self.buf_start += self.pos as u64;
+        self.buf = unprocessed;
+        self.buf.extend(chunk);
+        self.pos = 0;
+        self.fd_reader
+            .seek(SeekFrom::Start(self.buf_start + self.buf.len() as u64))?;
+        // there's no point in reading ahead past EOF (in follow mode, we'll request it again)
+        if got_new_data {
+            let request = self.next_request();
+            self.lock_prefetcher().request(request)?;
+        }
+        Ok(got_new_data)
+    }
+}
+
+impl Utf8CharSource for PrefetchStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        if self.pos >= self.buf.len() && !self.next_chunk()? {
+            return Ok(None); // EOF
+        }
+        let start_byte = self.buf[self.pos];
+        let n_bytes_in_char = get_width(start_byte);
+        if n_bytes_in_char == 0 {
+            self.pos += 1;
+            return Err(io::Error::other(format!(
+                "invalid UTF-8 start byte: {:x}",
+                start_byte
+            )));
+        }
+        // the char's remaining bytes might be in the next chunk
+        while self.buf.len() - self.pos < n_bytes_in_char {
+            if !self.next_chunk()? {
+                break; // EOF, in which case from_utf8 below will return an error
+            }
+        }
+        let end = (self.pos + n_bytes_in_char).min(self.buf.len());
+        let c = std::str::from_utf8(&self.buf[self.pos..end])
+            .map_err(|e| io::Error::other(format!("{}", e)))?
+            .chars()
+            .next();
+        self.pos = end;
+        Ok(c)
+    }
+}
+
+impl ParkCursorChars for PrefetchStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        self.park_cursor_before("").map(|_| ())
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        // (the chars might have been read from the previous chunk, but that was part of the file
+        // right before this one)
+        let new_pos = self.buf_start + self.pos as u64 - unread.len() as u64;
+        // data read ahead from the old position is of no use anymore
+        {
+            let mut prefetcher = self.lock_prefetcher();
+            prefetcher.wait()?;
+            prefetcher.arrived = None;
+        }
+        self.buf.clear();
+        self.pos = 0;
+        self.buf_start = new_pos;
+        self.fd_reader.seek(SeekFrom::Start(new_pos))?;
+        Ok(true)
+    }
+}
+
+impl Remainder for PrefetchStream {
+    fn remainder(&self) -> StreamData {
+        // data being read ahead is still in the file after the Python-visible position
+        StreamData::Bytes(self.buf[self.pos..].to_vec())
+    }
+}
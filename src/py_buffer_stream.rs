@@ -0,0 +1,123 @@
+/// Char source for data in Python objects supporting the buffer protocol.
+use crate::park_cursor::ParkCursorChars;
+use crate::py_err::TracebackDisplay;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use crate::utf8_reader::read_char_from_slice;
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyTypeError;
+use pyo3::types::{PyAny, PyAnyMethods, PyBytes, PyMemoryView};
+use pyo3::{Bound, Py, PyResult, Python};
+use std::io;
+
+/// Contents of a buffer: Either a view of the exporter's memory or a copy of it.
+enum BufferData {
+    View(PyBuffer<u8>),
+    Copy(Vec<u8>),
+}
+
+/// "Suitable" stream (see `SuitableStream`) that reads data from a Python object supporting the
+/// buffer protocol, e.g. `bytes`, `bytearray`, `memoryview` or `mmap`.
+///
+/// Buffers of immutable objects (`bytes` and memoryviews of them) are read from directly, holding
+/// a view of the buffer for the stream's lifetime. Other buffers could be modified while they're
+/// being read from (e.g. by another thread), so they're copied up front.
+pub struct PyBufferStream {
+    object: Py<PyAny>,
+    data: BufferData,
+    pos: usize,
+}
+
+/// Whether the given object's buffer is guaranteed never to change.
+fn is_immutable(object: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if object.is_exact_instance_of::<PyBytes>() {
+        return Ok(true);
+    }
+    if object.is_exact_instance_of::<PyMemoryView>() {
+        return Ok(object.getattr("obj")?.is_exact_instance_of::<PyBytes>());
+    }
+    Ok(false)
+}
+
+impl PyBufferStream {
+    /// Get the buffer of the given object.
+    ///
+    /// Returns `None` if the object doesn't support the buffer protocol.
+    pub fn new(object: &Bound<'_, PyAny>) -> PyResult<Option<Self>> {
+        let buffer = match PyBuffer::<u8>::get(object) {
+            Ok(buffer) => buffer,
+            Err(e) if e.is_instance_of::<PyTypeError>(object.py()) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if !buffer.is_c_contiguous() {
+            return Err(PyTypeError::new_err("buffer must be C-contiguous"));
+        }
+        let data = if is_immutable(object)? {
+            BufferData::View(buffer)
+        } else {
+            // (releases the view again right away)
+            BufferData::Copy(buffer.to_vec(object.py())?)
+        };
+        Ok(Some(PyBufferStream {
+            object: object.clone().unbind(),
+            data,
+            pos: 0,
+        }))
+    }
+
+    fn data(&self) -> &[u8] {
+        match &self.data {
+            // SAFETY: The buffer is C-contiguous and consists of `len_bytes()` bytes, as checked
+            // above and guaranteed by the buffer protocol. The memory stays valid until the view
+            // is released, which only happens when the `PyBuffer` is dropped, and it can't be
+            // modified in the meantime because views are only held for immutable objects.
+            BufferData::View(buffer) => unsafe {
+                std::slice::from_raw_parts(buffer.buf_ptr() as *const u8, buffer.len_bytes())
+            },
+            BufferData::Copy(data) => data,
+        }
+    }
+}
+
+impl Utf8CharSource for PyBufferStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        let mut pos = self.pos;
+        let c = read_char_from_slice(self.data(), &mut pos)?;
+        self.pos = pos;
+        Ok(c)
+    }
+}
+
+impl ParkCursorChars for PyBufferStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        // objects that have a position of their own (like mmap) get it set to ours
+        Python::attach(|py| -> PyResult<()> {
+            let object = self.object.bind(py);
+            if object.hasattr("seek")? {
+                object.call_method1("seek", (self.pos,))?;
+            }
+            Ok(())
+        })
+        .map_err(|e| {
+            io::Error::other(format!(
+                "Error seeking to offset {} in Python buffer object: {}\n{}",
+                self.pos,
+                e,
+                e.traceback_display(),
+            ))
+        })
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        // the chars were read from our data, so they're right before the current position
+        self.pos -= unread.len();
+        self.park_cursor()?;
+        Ok(true)
+    }
+}
+
+impl Remainder for PyBufferStream {
+    fn remainder(&self) -> StreamData {
+        StreamData::Bytes(self.data()[self.pos..].to_vec())
+    }
+}
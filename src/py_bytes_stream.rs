@@ -1,32 +1,105 @@
 use crate::py_common::PySeekWhence;
 use crate::py_err::TracebackDisplay;
+use pyo3::exceptions::{PyNotImplementedError, PyValueError};
+use pyo3::ffi;
 use pyo3::types::{PyAny, PyAnyMethods};
-use pyo3::{Py, PyResult, Python};
+use pyo3::{Bound, Py, PyErr, PyResult, Python};
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
+use std::os::raw::c_char;
 
 /// Python file-like object (= stream) that outputs bytes.
+///
+/// Data is read via `readinto()` if the stream supports it, which writes it into our buffer
+/// directly, and via `read()` otherwise.
 pub struct PyBytesStream {
     inner: Py<PyAny>,
+    use_readinto: bool,
 }
 
 impl PyBytesStream {
     pub fn new(inner: Py<PyAny>) -> Self {
-        PyBytesStream { inner }
+        let use_readinto = Python::attach(|py| inner.bind(py).hasattr("readinto").unwrap_or(false));
+        PyBytesStream {
+            inner,
+            use_readinto,
+        }
     }
-}
 
-impl Read for PyBytesStream {
+    /// Read into `buf` via the stream's `readinto()`, without any intermediate copies.
+    fn readinto(&self, py: Python<'_>, buf: &mut [u8]) -> PyResult<Option<usize>> {
+        // SAFETY: The memoryview points to `buf`, which is valid for writes of `buf.len()` bytes
+        // for the duration of this function. It is released below before we return, after which
+        // any remaining references to it on the Python side can no longer access the memory.
+        let view = unsafe {
+            Bound::from_owned_ptr_or_err(
+                py,
+                ffi::PyMemoryView_FromMemory(
+                    buf.as_mut_ptr() as *mut c_char,
+                    buf.len() as ffi::Py_ssize_t,
+                    ffi::PyBUF_WRITE,
+                ),
+            )?
+        };
+        let result = self.inner.bind(py).call_method1("readinto", (&view,));
+        // streams must not keep references to the buffer passed to readinto() beyond the call
+        // (same as for CPython's own buffered I/O, which does this exact thing), but in case
+        // one holds on to an export of it anyway, this at least fails instead of going unnoticed
+        view.call_method0("release")?;
+        // non-blocking streams return None if no data is available yet
+        let n_bytes_read = result?.extract::<Option<usize>>()?;
+        if n_bytes_read.is_some_and(|n| n > buf.len()) {
+            return Err(PyValueError::new_err(
+                "broken stream: readinto() returned more bytes than requested",
+            ));
+        }
+        Ok(n_bytes_read)
+    }
+
+    /// Read into `buf` via the stream's `read()`, which involves copying the returned bytes.
     // TODO Find out if there is a way to transfer this bytes vec in a zero-copy way from Py to Rs.
     // But I guess there can't be because for that we'd have to KNOW that it will never be read
     // again in Python (so the lifetime can be entirely in our hands), which we can't because there
     // is no way to annotate such facts in Python.
+    fn read_and_copy(&self, py: Python<'_>, buf: &mut [u8]) -> PyResult<Option<usize>> {
+        let Some(vec) = self
+            .inner
+            .bind(py)
+            .call_method1("read", (buf.len(),))?
+            .extract::<Option<Vec<u8>>>()?
+        else {
+            // non-blocking streams return None if no data is available yet
+            return Ok(None);
+        };
+        if vec.len() > buf.len() {
+            return Err(PyValueError::new_err(
+                "broken stream: read() returned more bytes than requested",
+            ));
+        }
+        buf[..vec.len()].clone_from_slice(&vec);
+        Ok(Some(vec.len()))
+    }
+}
+
+fn is_unsupported_operation(py: Python<'_>, e: &PyErr) -> bool {
+    e.is_instance_of::<PyNotImplementedError>(py)
+        || py
+            .import("io")
+            .and_then(|io| io.getattr("UnsupportedOperation"))
+            .is_ok_and(|t| e.is_instance(py, &t))
+}
+
+impl Read for PyBytesStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let vec = Python::attach(|py| -> PyResult<Vec<u8>> {
-            self.inner
-                .bind(py)
-                .call_method1("read", (buf.len(),))?
-                .extract::<Vec<u8>>()
+        Python::attach(|py| -> PyResult<Option<usize>> {
+            if self.use_readinto {
+                match self.readinto(py, buf) {
+                    // e.g. raw streams that only implement read()
+                    Err(e) if is_unsupported_operation(py, &e) => self.use_readinto = false,
+                    r => return r,
+                }
+            }
+            self.read_and_copy(py, buf)
         })
         .map_err(|e| {
             io::Error::other(format!(
@@ -35,9 +108,8 @@ impl Read for PyBytesStream {
                 e,
                 e.traceback_display(),
             ))
-        })?;
-        buf[..vec.len()].clone_from_slice(&vec);
-        Ok(vec.len())
+        })?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "no data available yet"))
     }
 }
 
@@ -0,0 +1,84 @@
+/// Char source for data that is pulled from a Python iterator of chunks.
+use crate::feed_stream::{FeedBuffer, FeedData, FeedError};
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use pyo3::exceptions::PyTypeError;
+use pyo3::types::{PyAnyMethods, PyIterator};
+use pyo3::{Py, PyResult, Python};
+use std::io;
+
+/// "Suitable" stream (see `SuitableStream`) that reads data from a Python iterator yielding
+/// chunks of `str` or `bytes`, like the ones returned by `requests.Response.iter_content()`.
+///
+/// UTF-8 sequences may be split across chunk boundaries. Chunks are pulled from the iterator one
+/// at a time whenever all previous ones have been read. Python exceptions raised by the iterator
+/// are passed through as they are.
+pub struct PyChunkStream {
+    iterator: Py<PyIterator>,
+    buffer: FeedBuffer,
+}
+
+impl PyChunkStream {
+    pub fn new(iterator: Py<PyIterator>) -> Self {
+        PyChunkStream {
+            iterator,
+            buffer: FeedBuffer::default(),
+        }
+    }
+
+    /// Pull the next chunk from the iterator into the buffer, closing it if there are no more.
+    fn pull_chunk(&mut self) -> io::Result<()> {
+        let chunk = Python::attach(|py| -> PyResult<Option<FeedData>> {
+            let Some(chunk) = self.iterator.bind(py).clone().next() else {
+                return Ok(None);
+            };
+            let chunk = chunk?;
+            match FeedData::from_py(&chunk)? {
+                Some(data) => Ok(Some(data)),
+                None => Err(PyTypeError::new_err(format!(
+                    "unsuitable chunk data type '{}'",
+                    chunk.get_type()
+                ))),
+            }
+        })
+        .map_err(io::Error::other)?;
+        match chunk {
+            Some(data) => self.buffer.feed(data),
+            None => self.buffer.close(),
+        }
+        .map_err(|e| match e {
+            FeedError::MixedTypes(t, previous) => io::Error::other(format!(
+                "got {t} chunk after having got {previous} chunks before"
+            )),
+            FeedError::TruncatedUtf8 => {
+                io::Error::other("chunks end in the middle of a UTF-8 sequence")
+            }
+            e => io::Error::other(e.to_string()),
+        })
+    }
+}
+
+impl Utf8CharSource for PyChunkStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        loop {
+            match self.buffer.read_char() {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => self.pull_chunk()?,
+                r => return r,
+            }
+        }
+    }
+}
+
+impl ParkCursorChars for PyChunkStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        // no-op
+        Ok(())
+    }
+}
+
+impl Remainder for PyChunkStream {
+    fn remainder(&self) -> StreamData {
+        self.buffer.remainder("")
+    }
+}
@@ -0,0 +1,101 @@
+/// Char source for data in a Python `str` object.
+use crate::park_cursor::ParkCursorChars;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+#[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+use pyo3::types::PyStringData;
+use pyo3::types::{PyString, PyStringMethods};
+use pyo3::{Bound, Py, PyResult};
+use std::io;
+
+/// "Suitable" stream (see `SuitableStream`) that reads chars from a Python `str` object.
+///
+/// Where possible, chars are read directly from the string's native (PEP 393) storage, which is
+/// Latin-1, UCS-2 or UCS-4 depending on the string's contents, so no data is copied. On Python
+/// implementations that don't expose it, the string is copied into a Rust `String` once instead.
+pub struct PyStrStream {
+    // keeps the string's storage alive
+    _string: Py<PyString>,
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    data: PyStringData<'static>,
+    #[cfg(any(Py_LIMITED_API, GraalPy, PyPy))]
+    data: String,
+    pos: usize,
+}
+
+impl PyStrStream {
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    pub fn new(string: &Bound<'_, PyString>) -> PyResult<Self> {
+        // SAFETY: Python strings are immutable and `_string` keeps this one alive for as long as
+        // `self` exists, so its storage stays valid and unchanged until then. The 'static
+        // lifetime never escapes `self`.
+        let data = unsafe {
+            std::mem::transmute::<PyStringData<'_>, PyStringData<'static>>(string.data()?)
+        };
+        Ok(PyStrStream {
+            _string: string.clone().unbind(),
+            data,
+            pos: 0,
+        })
+    }
+
+    #[cfg(any(Py_LIMITED_API, GraalPy, PyPy))]
+    pub fn new(string: &Bound<'_, PyString>) -> PyResult<Self> {
+        Ok(PyStrStream {
+            _string: string.clone().unbind(),
+            data: string.to_str()?.to_owned(),
+            pos: 0,
+        })
+    }
+
+    /// Char at the given position (in code points or, for the fallback, bytes) and its length.
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    fn char_at(&self, pos: usize) -> io::Result<Option<(char, usize)>> {
+        let code_point = match self.data {
+            PyStringData::Ucs1(data) => data.get(pos).map(|&b| u32::from(b)),
+            PyStringData::Ucs2(data) => data.get(pos).map(|&x| u32::from(x)),
+            PyStringData::Ucs4(data) => data.get(pos).copied(),
+        };
+        code_point
+            .map(|x| {
+                char::from_u32(x)
+                    .map(|c| (c, 1))
+                    .ok_or_else(|| io::Error::other(format!("invalid code point in string: {x:x}")))
+            })
+            .transpose()
+    }
+
+    #[cfg(any(Py_LIMITED_API, GraalPy, PyPy))]
+    fn char_at(&self, pos: usize) -> io::Result<Option<(char, usize)>> {
+        Ok(self.data[pos..].chars().next().map(|c| (c, c.len_utf8())))
+    }
+}
+
+impl Utf8CharSource for PyStrStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        Ok(self.char_at(self.pos)?.map(|(c, len)| {
+            self.pos += len;
+            c
+        }))
+    }
+}
+
+impl ParkCursorChars for PyStrStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        // no-op
+        Ok(())
+    }
+}
+
+impl Remainder for PyStrStream {
+    fn remainder(&self) -> StreamData {
+        let mut s = String::new();
+        let mut pos = self.pos;
+        // can only fail for lone surrogates, at which point the rest is unusable anyway
+        while let Ok(Some((c, len))) = self.char_at(pos) {
+            s.push(c);
+            pos += len;
+        }
+        StreamData::Text(s)
+    }
+}
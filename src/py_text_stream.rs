@@ -28,11 +28,11 @@ impl ReadString for PyTextStream {
     // again in Python (so the lifetime can be entirely in our hands), which we can't because there
     // is no way to annotate such facts in Python.
     fn read_string(&mut self, size: usize) -> io::Result<String> {
-        Python::attach(|py| -> PyResult<String> {
+        Python::attach(|py| -> PyResult<Option<String>> {
             self.inner
                 .bind(py)
                 .call_method1("read", (size,))?
-                .extract::<String>()
+                .extract::<Option<String>>()
         })
         .map_err(|e| {
             io::Error::other(format!(
@@ -41,7 +41,9 @@ impl ReadString for PyTextStream {
                 e,
                 e.traceback_display(),
             ))
-        })
+        })?
+        // non-blocking streams return None if no data is available yet
+        .ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "no data available yet"))
     }
 }
 
@@ -52,15 +54,6 @@ impl OpaqueSeek for PyTextStream {
         Python::attach(|py| {
             let (offset, whence) = match pos {
                 OpaqueSeekFrom::Start(x) => (x, PySeekWhence::Set),
-                OpaqueSeekFrom::Current => (
-                    PyOpaqueSeekPos(
-                        0_u8.into_pyobject(py)
-                            .unwrap_infallible()
-                            .unbind()
-                            .into_any(),
-                    ),
-                    PySeekWhence::Cur,
-                ),
                 OpaqueSeekFrom::End => (
                     PyOpaqueSeekPos(
                         0_u8.into_pyobject(py)
@@ -89,4 +82,15 @@ impl OpaqueSeek for PyTextStream {
             }
         })
     }
+
+    fn tell(&mut self) -> io::Result<PyOpaqueSeekPos> {
+        Python::attach(|py| match self.inner.bind(py).call_method0("tell") {
+            Ok(x) => Ok(PyOpaqueSeekPos(x.unbind())),
+            Err(e) => Err(io::Error::other(format!(
+                "Error getting position in Python text stream: {}\n{}",
+                e,
+                e.traceback_display(),
+            ))),
+        })
+    }
 }
@@ -0,0 +1,41 @@
+/// Formats of streams made up of a sequence of separate JSON records.
+use pyo3::create_exception;
+use pyo3::exceptions::PyUserWarning;
+
+/// Record separator (RS) character that precedes each record in RFC 7464 JSON text sequences.
+pub const RECORD_SEPARATOR: char = '\u{1e}';
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    /// Newline-delimited JSON (JSON Lines): records are separated by newlines.
+    Ndjson,
+    /// RFC 7464 JSON text sequences (application/json-seq): each record is preceded by an RS
+    /// character.
+    JsonSeq,
+}
+
+impl RecordFormat {
+    /// Character that separates records in this format.
+    pub fn separator(self) -> char {
+        match self {
+            RecordFormat::Ndjson => '\n',
+            RecordFormat::JsonSeq => RECORD_SEPARATOR,
+        }
+    }
+
+    /// Human-readable name of the separator for use in error messages.
+    pub fn separator_name(self) -> &'static str {
+        match self {
+            RecordFormat::Ndjson => "end of line",
+            RecordFormat::JsonSeq => "record separator",
+        }
+    }
+}
+
+create_exception!(
+    json_stream_rs_tokenizer,
+    TruncatedValueWarning,
+    PyUserWarning,
+    "Warning emitted when a possibly truncated top-level value in a JSON text sequence is \
+    discarded."
+);
@@ -0,0 +1,168 @@
+use crate::park_cursor::ParkCursorChars;
+use crate::py_err::TracebackDisplay;
+use crate::remainder::{Remainder, StreamData};
+use crate::utf8_char_source::Utf8CharSource;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyAny, PyAnyMethods};
+use pyo3::{Py, PyResult, Python};
+use std::io;
+use utf8_width::get_width;
+
+/// Python unseekable bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
+///
+/// This means that the necessary traits (see below) are implemented for it.
+///
+/// This is the variant for unseekable streams that have `peek()` and `read1()` methods, like
+/// `io.BufferedReader`. Data is read from the stream's own buffer via `peek()`, which doesn't
+/// consume it, and only the data that has actually been processed is consumed via `read1()`,
+/// so parking the cursor is possible without reading char-by-char.
+pub struct SuitablePeekableBufferedBytesStream {
+    inner: Py<PyAny>,
+    // data obtained via the last peek(), of which the first `pos` bytes have been processed
+    buf: Vec<u8>,
+    pos: usize,
+    // number of bytes at the start of `buf` that have already been consumed from the stream
+    consumed: usize,
+}
+
+impl SuitablePeekableBufferedBytesStream {
+    pub fn new(inner: Py<PyAny>) -> Self {
+        SuitablePeekableBufferedBytesStream {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Consume the given number of bytes (which must have been peeked before) from the stream.
+    fn consume(&self, py: Python<'_>, n: usize) -> PyResult<()> {
+        if n == 0 {
+            return Ok(());
+        }
+        let consumed = self
+            .inner
+            .bind(py)
+            .call_method1("read1", (n,))?
+            .extract::<Vec<u8>>()?;
+        if consumed.len() != n {
+            return Err(PyValueError::new_err(format!(
+                "broken stream: read1() returned {} bytes instead of {} previously peeked ones",
+                consumed.len(),
+                n
+            )));
+        }
+        Ok(())
+    }
+
+    /// Consume the peeked data and peek at the data after it.
+    ///
+    /// Bytes that haven't been processed yet (at most those of an incomplete char) are kept at
+    /// the start of the new buffer, as otherwise `peek()` would only return them again instead
+    /// of also returning the data after them. Returns whether any new data was obtained.
+    ///
+    /// `peek()` returns no data both at EOF and if no data is available yet from a non-blocking
+    /// stream, so in that case, a single byte is read to tell these apart. The latter results in
+    /// an error of kind `WouldBlock`.
+    fn refill(&mut self) -> io::Result<bool> {
+        Python::attach(|py| -> PyResult<Option<bool>> {
+            self.consume(py, self.buf.len() - self.consumed)?;
+            let unprocessed = self.buf.split_off(self.pos);
+            let peeked = self
+                .inner
+                .bind(py)
+                .call_method1("peek", (1,))?
+                .extract::<Vec<u8>>()?;
+            self.consumed = unprocessed.len();
+            self.buf = unprocessed;
+            self.pos = 0;
+            if !peeked.is_empty() {
+                self.buf.extend(peeked);
+                return Ok(Some(true));
+            }
+            let probed = self.inner.bind(py).call_method1("read", (1,))?;
+            if probed.is_none() {
+                return Ok(None);
+            }
+            let probed = probed.extract::<Vec<u8>>()?;
+            // the probed byte has already been consumed from the stream, unlike peeked ones
+            self.consumed += probed.len();
+            self.buf.extend(&probed);
+            Ok(Some(!probed.is_empty()))
+        })
+        .map_err(|e| {
+            io::Error::other(format!(
+                "Error peeking into Python bytes stream: {}\n{}",
+                e,
+                e.traceback_display(),
+            ))
+        })?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::WouldBlock, "no data available yet"))
+    }
+}
+
+impl Utf8CharSource for SuitablePeekableBufferedBytesStream {
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        if self.pos >= self.buf.len() && !self.refill()? {
+            return Ok(None); // EOF
+        }
+        let start_byte = self.buf[self.pos];
+        let n_bytes_in_char = get_width(start_byte);
+        if n_bytes_in_char == 0 {
+            self.pos += 1;
+            return Err(io::Error::other(format!(
+                "invalid UTF-8 start byte: {:x}",
+                start_byte
+            )));
+        }
+        // the char's remaining bytes might not have been peeked yet
+        while self.buf.len() - self.pos < n_bytes_in_char {
+            if !self.refill()? {
+                break; // EOF, in which case from_utf8 below will return an error
+            }
+        }
+        let end = (self.pos + n_bytes_in_char).min(self.buf.len());
+        let c = std::str::from_utf8(&self.buf[self.pos..end])
+            .map_err(|e| io::Error::other(format!("{}", e)))?
+            .chars()
+            .next();
+        self.pos = end;
+        Ok(c)
+    }
+}
+
+impl ParkCursorChars for SuitablePeekableBufferedBytesStream {
+    fn park_cursor(&mut self) -> io::Result<()> {
+        let n_bytes_to_consume = self.pos.saturating_sub(self.consumed);
+        Python::attach(|py| self.consume(py, n_bytes_to_consume)).map_err(|e| {
+            io::Error::other(format!(
+                "Error consuming {} bytes from Python bytes stream: {}\n{}",
+                n_bytes_to_consume,
+                e,
+                e.traceback_display(),
+            ))
+        })?;
+        self.buf.drain(..self.pos);
+        self.consumed = self.consumed.saturating_sub(self.pos);
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        // the chars' bytes are still in the buffer unless they were split across peeks
+        let moved_back = unread.len() <= self.pos;
+        if moved_back {
+            self.pos -= unread.len();
+        }
+        self.park_cursor()?;
+        Ok(moved_back || unread.is_empty())
+    }
+}
+
+impl Remainder for SuitablePeekableBufferedBytesStream {
+    fn remainder(&self) -> StreamData {
+        // peeked bytes that haven't been consumed are still in the stream, so only the
+        // unprocessed bytes of an incomplete char carried over by refill() can be missing from it
+        StreamData::Bytes(self.buf[self.pos.min(self.consumed)..self.consumed].to_vec())
+    }
+}
@@ -1,59 +1,60 @@
+use crate::buffer_size::{BufferSize, SizeLimitedReader};
 use crate::park_cursor::ParkCursorChars;
-use crate::py_bytes_stream::PyBytesStream;
 use crate::remainder::{Remainder, StreamData};
 use crate::utf8_char_source::Utf8CharSource;
+use crate::utf8_reader::read_char;
 use std::io;
-use std::io::{Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
 use std::mem::take;
-use utf8_read::{Char, Reader};
+use utf8_read::Reader;
 
-/// Python bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
+/// Bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
 ///
 /// This means that the necessary traits (see below) are implemented for it.
-pub struct SuitableSeekableBufferedBytesStream {
+pub struct SuitableSeekableBufferedBytesStream<R: Read + Seek> {
     // note that this is not actually optional, it's just a shitty hack because I'm too dumb to
     // placate Rust when temporarily moving the reader out of the struct within a method...
-    reader: Option<Reader<PyBytesStream>>,
+    reader: Option<Reader<SizeLimitedReader<R>>>,
+    follow: bool,
 }
 
-impl SuitableSeekableBufferedBytesStream {
-    pub fn new(inner: PyBytesStream, bufsize: usize) -> Self {
+impl<R: Read + Seek> SuitableSeekableBufferedBytesStream<R> {
+    /// If `follow` is set, EOF is not considered final (see `FollowStream`).
+    pub fn new(inner: R, bufsize: BufferSize, follow: bool) -> Self {
+        let inner = SizeLimitedReader::new(inner, bufsize);
         SuitableSeekableBufferedBytesStream {
-            reader: Some(Reader::with_chunk_size(inner, bufsize)),
+            reader: Some(Reader::with_chunk_size(inner, bufsize.max()).set_eof_on_no_data(!follow)),
+            follow,
         }
     }
 }
 
-impl Utf8CharSource for SuitableSeekableBufferedBytesStream {
+impl<R: Read + Seek> Utf8CharSource for SuitableSeekableBufferedBytesStream<R> {
     fn read_char(&mut self) -> io::Result<Option<char>> {
-        Ok(
-            match self
-                .reader
-                .as_mut()
-                .unwrap()
-                .next_char()
-                .map_err(|e| io::Error::other(format!("{}", e)))?
-            {
-                Char::Eof => None,
-                Char::Char(c) => Some(c),
-                Char::NoData => None, // for us this means the same as EOF I guess?
-            },
-        )
+        read_char(self.reader.as_mut().unwrap())
     }
 }
 
-impl ParkCursorChars for SuitableSeekableBufferedBytesStream {
+impl<R: Read + Seek> ParkCursorChars for SuitableSeekableBufferedBytesStream<R> {
     fn park_cursor(&mut self) -> io::Result<()> {
+        self.park_cursor_before("").map(|_| ())
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
         let reader = take(&mut self.reader);
         let (mut inner, _pos, rem_buffered_bytes) = reader.unwrap().complete();
-        inner.seek(SeekFrom::Current(-(rem_buffered_bytes.len() as i64)))?;
+        inner.seek(SeekFrom::Current(
+            -((rem_buffered_bytes.len() + unread.len()) as i64),
+        ))?;
         // TODO this should be done even if ^ returns an error:
-        self.reader = Some(Reader::new(inner));
-        Ok(())
+        let chunk_size = inner.max_size();
+        self.reader =
+            Some(Reader::with_chunk_size(inner, chunk_size).set_eof_on_no_data(!self.follow));
+        Ok(true)
     }
 }
 
-impl Remainder for SuitableSeekableBufferedBytesStream {
+impl<R: Read + Seek> Remainder for SuitableSeekableBufferedBytesStream<R> {
     fn remainder(&self) -> StreamData {
         StreamData::Bytes(match &self.reader {
             Some(reader) => reader.borrow_buffer().to_owned(),
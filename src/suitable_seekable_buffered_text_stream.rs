@@ -1,3 +1,4 @@
+use crate::buffer_size::BufferSize;
 use crate::opaque_seek::{OpaqueSeek, OpaqueSeekFrom};
 use crate::park_cursor::ParkCursorChars;
 use crate::py_text_stream::{PyOpaqueSeekPos, PyTextStream};
@@ -11,16 +12,22 @@ use std::iter::Iterator;
 /// Python text stream wrapper that makes it "suitable" for use in the Tokenizer.
 ///
 /// This means that the necessary traits (see below) are implemented for it.
+///
+/// Seek positions of text streams are opaque, so to park the cursor, we have to seek to the
+/// position obtained via `tell()` before reading the current buffer and then read as many chars
+/// as have been processed since. Recording positions within the buffer as well would reduce the
+/// amount of data read again, but `tell()` isn't cheap, and parking the cursor is followed by
+/// reading a whole new buffer anyway.
 pub struct SuitableSeekableBufferedTextStream {
     inner: PyTextStream,
-    buffer_size: usize,
+    buffer_size: BufferSize,
     chars_iter: OwnedChars,
     chars_read_from_buf: usize,
     buf_start_seek_pos: Option<PyOpaqueSeekPos>,
 }
 
 impl SuitableSeekableBufferedTextStream {
-    pub fn new(inner: PyTextStream, buffer_size: usize) -> Self {
+    pub fn new(inner: PyTextStream, buffer_size: BufferSize) -> Self {
         SuitableSeekableBufferedTextStream {
             inner,
             buffer_size,
@@ -29,39 +36,59 @@ impl SuitableSeekableBufferedTextStream {
             buf_start_seek_pos: None,
         }
     }
+
+    fn refill(&mut self) -> io::Result<()> {
+        let seek_pos = self.inner.tell()?;
+        let buf = self.inner.read_string(self.buffer_size.next_size())?;
+        self.buf_start_seek_pos = Some(seek_pos);
+        self.chars_iter = OwnedCharsExt::into_chars(buf);
+        self.chars_read_from_buf = 0;
+        Ok(())
+    }
 }
 
 impl Utf8CharSource for SuitableSeekableBufferedTextStream {
     fn read_char(&mut self) -> io::Result<Option<char>> {
-        if let Some(c) = self.chars_iter.next() {
+        if self.chars_iter.as_str().is_empty() {
+            self.refill()?;
+        }
+        let oc = self.chars_iter.next();
+        if oc.is_some() {
             self.chars_read_from_buf += 1;
-            Ok(Some(c))
-        } else {
-            // TODO: I don't think this can handle actually getting to EOF very well (buf size
-            // becomes 0? => no seek), but probably not relevant
-            self.buf_start_seek_pos = Some(self.inner.seek(OpaqueSeekFrom::Current)?);
-            let buf = self.inner.read_string(self.buffer_size)?;
-            self.chars_iter = OwnedCharsExt::into_chars(buf);
-            self.chars_read_from_buf = 0;
-            let oc = self.chars_iter.next();
-            if oc.is_some() {
-                self.chars_read_from_buf += 1;
-            }
-            Ok(oc)
         }
+        Ok(oc)
     }
 }
 
 impl ParkCursorChars for SuitableSeekableBufferedTextStream {
     fn park_cursor(&mut self) -> io::Result<()> {
-        let chars_read_from_buf = self.chars_read_from_buf;
-        if let Some(buf_start_seek_pos) = &self.buf_start_seek_pos {
-            self.inner
-                .seek(OpaqueSeekFrom::Start(buf_start_seek_pos.clone()))?;
-            self.inner.read_string(chars_read_from_buf)?;
-            self.chars_iter = OwnedChars::from_string("".to_owned());
+        self.park_cursor_before("").map(|_| ())
+    }
+
+    fn park_cursor_before(&mut self, unread: &str) -> io::Result<bool> {
+        let n_unread_chars = unread.chars().count();
+        // chars from a previous buffer can't be returned to as its start position is gone
+        let moved_back = n_unread_chars <= self.chars_read_from_buf;
+        // if all buffered chars have been processed (incl. at EOF), the cursor already is where
+        // it should be
+        if self.chars_iter.as_str().is_empty() && (n_unread_chars == 0 || !moved_back) {
+            return Ok(n_unread_chars == 0);
         }
-        Ok(())
+        let chars_read_from_buf = if moved_back {
+            self.chars_read_from_buf - n_unread_chars
+        } else {
+            self.chars_read_from_buf
+        };
+        if let Some(seek_pos) = &self.buf_start_seek_pos {
+            self.inner.seek(OpaqueSeekFrom::Start(seek_pos.clone()))?;
+            if chars_read_from_buf > 0 {
+                self.inner.read_string(chars_read_from_buf)?;
+            }
+        }
+        self.chars_iter = OwnedChars::from_string("".to_owned());
+        self.chars_read_from_buf = 0;
+        self.buf_start_seek_pos = None;
+        Ok(moved_back)
     }
 }
 
@@ -2,10 +2,20 @@
 ///
 /// "Suitable" means that it implements ParkCursorChars (too lazy to make another trait for that),
 /// although it can be a no-op.
+use crate::buffer_size::BufferSize;
+use crate::decompression::{CompressionMode, DecompressingReader, SharedCompressionOffsets};
+#[cfg(unix)]
+use crate::fd_reader::FdReader;
 use crate::park_cursor::ParkCursorChars;
+#[cfg(unix)]
+use crate::prefetch_stream::PrefetchStream;
+use crate::py_buffer_stream::PyBufferStream;
 use crate::py_bytes_stream::PyBytesStream;
+use crate::py_chunk_stream::PyChunkStream;
+use crate::py_str_stream::PyStrStream;
 use crate::py_text_stream::PyTextStream;
 use crate::remainder::Remainder;
+use crate::suitable_peekable_buffered_bytes_stream::SuitablePeekableBufferedBytesStream;
 use crate::suitable_seekable_buffered_bytes_stream::SuitableSeekableBufferedBytesStream;
 use crate::suitable_seekable_buffered_text_stream::SuitableSeekableBufferedTextStream;
 use crate::suitable_unbuffered_bytes_stream::SuitableUnbufferedBytesStream;
@@ -14,7 +24,8 @@ use crate::suitable_unseekable_buffered_bytes_stream::SuitableUnseekableBuffered
 use crate::suitable_unseekable_buffered_text_stream::SuitableUnseekableBufferedTextStream;
 use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::types::{PyAny, PyAnyMethods, PyBytes, PyString};
-use pyo3::{Py, PyResult, Python};
+use pyo3::{FromPyObject, Py, PyErr, PyResult, Python};
+use std::io::{Read, Seek};
 
 const DEFAULT_BUFSIZE: usize = 8000;
 
@@ -28,6 +39,27 @@ enum ReadReturnType {
     Other(String),
 }
 
+/// Type of the data returned by a stream's `read()` method, if given explicitly.
+#[derive(Clone, Copy)]
+pub enum StreamMode {
+    Text,
+    Bytes,
+}
+
+impl StreamMode {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        Ok(match name {
+            "text" => StreamMode::Text,
+            "bytes" => StreamMode::Bytes,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown mode '{name}', must be 'bytes' or 'text'"
+                )))
+            }
+        })
+    }
+}
+
 fn determine_read_return_type(stream: &Py<PyAny>) -> PyResult<ReadReturnType> {
     Python::attach(|py| -> PyResult<ReadReturnType> {
         let read_result = stream.bind(py).call_method1("read", (0,))?;
@@ -41,53 +73,119 @@ fn determine_read_return_type(stream: &Py<PyAny>) -> PyResult<ReadReturnType> {
     })
 }
 
+/// Whether the stream supports `peek()` and `read1()`, like `io.BufferedReader`.
+fn is_peekable(stream: &Py<PyAny>) -> PyResult<bool> {
+    Python::attach(|py| -> PyResult<bool> {
+        let stream = stream.bind(py);
+        Ok(stream.hasattr("peek")? && stream.hasattr("read1")?)
+    })
+}
+
+/// Whether the stream is seekable according to its `seekable()` method, if it has one.
 fn is_seekable(stream: &Py<PyAny>) -> PyResult<bool> {
     Python::attach(|py| -> PyResult<bool> {
-        stream
-            .bind(py)
-            .call_method1("seekable", ())?
-            .extract::<bool>()
+        let stream = stream.bind(py);
+        if !stream.hasattr("seekable")? {
+            return Ok(false);
+        }
+        stream.call_method0("seekable")?.extract::<bool>()
     })
 }
 
+fn has_seek_method(stream: &Py<PyAny>) -> PyResult<bool> {
+    Python::attach(|py| -> PyResult<bool> { stream.bind(py).hasattr("seek") })
+}
+
+/// Value of the tokenizer's `buffering` argument: a buffer size or the name of a strategy.
+#[derive(FromPyObject)]
+pub enum BufferingArg {
+    Size(i64),
+    Strategy(String),
+}
+
 pub enum BufferingMode {
     Unbuffered,
     DontCare,
     BufferedWithSize(usize),
+    // start with a small buffer and grow it (see `BufferSize`)
+    Adaptive,
+}
+
+impl BufferingMode {
+    pub fn from_arg(arg: BufferingArg) -> PyResult<Self> {
+        Ok(match arg {
+            BufferingArg::Size(size) if size < 0 => BufferingMode::DontCare,
+            BufferingArg::Size(0 | 1) => BufferingMode::Unbuffered,
+            BufferingArg::Size(size) => BufferingMode::BufferedWithSize(
+                size.try_into()
+                    .map_err(|_| PyValueError::new_err("buffer size too large"))?,
+            ),
+            BufferingArg::Strategy(name) if name == "adaptive" => BufferingMode::Adaptive,
+            BufferingArg::Strategy(name) => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown buffering strategy '{name}', must be 'adaptive' (or an integer)"
+                )))
+            }
+        })
+    }
 }
 
 #[derive(Debug, PartialEq)]
 enum StreamSettings {
     Unbuffered,
-    UnseekableBuffered(usize),
-    SeekableBuffered(usize),
+    UnseekableBuffered(BufferSize),
+    SeekableBuffered(BufferSize),
+    // buffer size is determined by the stream
+    PeekableBuffered,
+}
+
+impl StreamSettings {
+    /// Buffer size to use for reading, if it's up to us.
+    fn buffer_size(&self) -> Option<BufferSize> {
+        match self {
+            StreamSettings::UnseekableBuffered(bufsize)
+            | StreamSettings::SeekableBuffered(bufsize) => Some(*bufsize),
+            StreamSettings::Unbuffered | StreamSettings::PeekableBuffered => None,
+        }
+    }
 }
 
 fn decide_stream_settings(
     correct_cursor: bool,
     buffering: BufferingMode,
     seekable: bool,
+    peekable: bool,
 ) -> PyResult<StreamSettings> {
     Ok(match buffering {
         BufferingMode::Unbuffered => StreamSettings::Unbuffered,
-        BufferingMode::DontCare => {
+        BufferingMode::DontCare | BufferingMode::Adaptive => {
+            let bufsize = match buffering {
+                BufferingMode::Adaptive => BufferSize::adaptive(),
+                _ => BufferSize::fixed(DEFAULT_BUFSIZE),
+            };
             if !correct_cursor {
-                StreamSettings::UnseekableBuffered(DEFAULT_BUFSIZE)
+                StreamSettings::UnseekableBuffered(bufsize)
             } else if seekable {
-                StreamSettings::SeekableBuffered(DEFAULT_BUFSIZE)
+                StreamSettings::SeekableBuffered(bufsize)
+            } else if peekable {
+                StreamSettings::PeekableBuffered
             } else {
                 StreamSettings::Unbuffered
             }
         }
         BufferingMode::BufferedWithSize(bufsize) => {
+            let bufsize = BufferSize::fixed(bufsize);
             if !correct_cursor {
                 StreamSettings::UnseekableBuffered(bufsize)
             } else if seekable {
                 StreamSettings::SeekableBuffered(bufsize)
+            } else if peekable {
+                StreamSettings::PeekableBuffered
             } else {
                 return Err(PyValueError::new_err(
                     "Incompatible stream requirements: correct_cursor and a buffer size > 1 \
-                    are only possible if the given stream is seekable, which this one is not"
+                    are only possible if the given stream is seekable or supports peek() and \
+                    read1(), which this one does not"
                         .to_string(),
                 ));
             }
@@ -95,19 +193,266 @@ fn decide_stream_settings(
     })
 }
 
+fn has_read_method(stream: &Py<PyAny>) -> PyResult<bool> {
+    Python::attach(|py| -> PyResult<bool> { stream.bind(py).hasattr("read") })
+}
+
+fn make_chunk_stream(stream: &Py<PyAny>) -> PyResult<(PyChunkStream, Py<PyAny>)> {
+    Python::attach(|py| -> PyResult<(PyChunkStream, Py<PyAny>)> {
+        let iterator = stream.bind(py).try_iter().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "expected a file-like object (i.e. one with a read() method), a str, an object \
+                supporting the buffer protocol or an iterable of str or bytes chunks, got '{}'",
+                stream.bind(py).get_type()
+            ))
+        })?;
+        let source = iterator.clone().into_any().unbind();
+        Ok((PyChunkStream::new(iterator.unbind()), source))
+    })
+}
+
+fn make_str_stream(stream: &Py<PyAny>) -> PyResult<Option<PyStrStream>> {
+    Python::attach(|py| -> PyResult<Option<PyStrStream>> {
+        match stream.bind(py).cast::<PyString>() {
+            Ok(s) => Ok(Some(PyStrStream::new(s)?)),
+            Err(_) => Ok(None),
+        }
+    })
+}
+
+fn make_buffer_stream(stream: &Py<PyAny>) -> PyResult<Option<PyBufferStream>> {
+    Python::attach(|py| PyBufferStream::new(stream.bind(py)))
+}
+
+#[cfg(unix)]
+fn make_fd_reader(stream: &Py<PyAny>, seekable: bool) -> PyResult<Option<FdReader>> {
+    Python::attach(|py| FdReader::new(stream.bind(py), seekable))
+}
+
+#[cfg(not(unix))]
+fn make_fd_reader(_stream: &Py<PyAny>, _seekable: bool) -> PyResult<Option<PyBytesStream>> {
+    Ok(None)
+}
+
+fn make_bytes_stream<R: Read + Seek + Send + Sync + 'static>(
+    inner: R,
+    stream_settings: StreamSettings,
+    follow: bool,
+) -> Box<dyn SuitableStream + Send + Sync> {
+    match stream_settings {
+        StreamSettings::Unbuffered => Box::new(SuitableUnbufferedBytesStream::new(inner, follow)),
+        StreamSettings::UnseekableBuffered(bufsize) => Box::new(
+            SuitableUnseekableBufferedBytesStream::new(inner, bufsize, follow),
+        ),
+        StreamSettings::SeekableBuffered(bufsize) => Box::new(
+            SuitableSeekableBufferedBytesStream::new(inner, bufsize, follow),
+        ),
+        // needs the Python stream itself, so it's handled separately
+        StreamSettings::PeekableBuffered => Box::new(SuitableUnseekableBufferedBytesStream::new(
+            inner,
+            BufferSize::fixed(DEFAULT_BUFSIZE),
+            follow,
+        )),
+    }
+}
+
+fn make_decompressing_stream<R: Read + Send + 'static>(
+    inner: R,
+    stream_settings: StreamSettings,
+    mode: CompressionMode,
+    offsets: SharedCompressionOffsets,
+) -> PyResult<Box<dyn SuitableStream + Send + Sync>> {
+    let reader = DecompressingReader::new(inner, mode, offsets)?;
+    Ok(match stream_settings {
+        StreamSettings::Unbuffered => Box::new(SuitableUnbufferedBytesStream::new(reader, false)),
+        // seeking in the decompressed data is impossible, which the tokenizer ensures
+        // doesn't matter by not allowing correct_cursor in combination with decompression
+        StreamSettings::UnseekableBuffered(bufsize) | StreamSettings::SeekableBuffered(bufsize) => {
+            Box::new(SuitableUnseekableBufferedBytesStream::new(
+                reader, bufsize, false,
+            ))
+        }
+        StreamSettings::PeekableBuffered => Box::new(SuitableUnseekableBufferedBytesStream::new(
+            reader,
+            BufferSize::fixed(DEFAULT_BUFSIZE),
+            false,
+        )),
+    })
+}
+
+fn compression_unsupported() -> PyErr {
+    PyValueError::new_err(
+        "compression is only supported for binary file-like objects (wrap other objects in \
+        io.BytesIO if necessary)",
+    )
+}
+
+/// "Suitable" stream along with information about the source of its data.
+pub struct StreamAndSource {
+    pub stream: Box<dyn SuitableStream + Send + Sync>,
+    /// Python object to continue reading from after the stream's data.
+    pub source: Option<Py<PyAny>>,
+    /// Whether the stream needs the GIL at most once per chunk it reads and its data can't be
+    /// modified by Python code, so that it's worth and safe to release the GIL while reading
+    /// chars from it.
+    pub reads_without_gil: bool,
+}
+
+/// Wrap the given Python object in an appropriate "suitable" stream.
+///
+/// Also returns the Python object from which data following the data read by the stream can be
+/// obtained, which is the object itself for file-like objects, the iterator over the chunks for
+/// iterables and `None` for sources that are read in their entirety from the start.
+///
+/// `str` objects and objects supporting the buffer protocol are read from directly, the latter
+/// even if they also have a `read` method (like `mmap`). Objects without a `read` method are
+/// treated as iterables of chunks. In all of these cases, `buffering` has no effect.
+///
+/// For buffered reading from certain kinds of Python file objects (see `FdReader`), the file
+/// descriptor is read from directly, bypassing the Python object.
+///
+/// If `follow` is set, EOF reached in the returned stream is not considered final, i.e. reading
+/// from it again can yield more data if the underlying stream has grown in the meantime.
+///
+/// If `compression` is given, the data is decompressed according to its mode, updating its
+/// offsets as it goes. This is only possible for binary file-like objects and can't be combined
+/// with `correct_cursor` or `follow`.
+///
+/// If `prefetch` is set, seekable files whose descriptors are read from directly are read from in
+/// a background thread instead (see `PrefetchStream`). It has no effect for other streams.
+#[allow(clippy::too_many_arguments)]
 pub fn make_suitable_stream(
     stream: Py<PyAny>,
     buffering: BufferingMode,
     correct_cursor: bool,
-) -> PyResult<Box<dyn SuitableStream + Send + Sync>> {
-    let read_return_type: ReadReturnType = determine_read_return_type(&stream)?;
-    let seekable: bool = is_seekable(&stream)?;
-    let stream_settings = decide_stream_settings(correct_cursor, buffering, seekable)?;
-    Ok(match read_return_type {
+    follow: bool,
+    compression: Option<(CompressionMode, SharedCompressionOffsets)>,
+    mode: Option<StreamMode>,
+    seekable: Option<bool>,
+    prefetch: bool,
+) -> PyResult<StreamAndSource> {
+    // sources that aren't streams in the file-like object sense
+    let direct_stream: Option<StreamAndSource> = if let Some(str_stream) = make_str_stream(&stream)?
+    {
+        Some(StreamAndSource {
+            stream: Box::new(str_stream),
+            source: None,
+            reads_without_gil: true,
+        })
+    } else if let Some(buffer_stream) = make_buffer_stream(&stream)? {
+        Some(StreamAndSource {
+            stream: Box::new(buffer_stream),
+            source: None,
+            reads_without_gil: true,
+        })
+    } else if !has_read_method(&stream)? {
+        let (chunk_stream, iterator) = make_chunk_stream(&stream)?;
+        Some(StreamAndSource {
+            stream: Box::new(chunk_stream),
+            source: Some(iterator),
+            reads_without_gil: false,
+        })
+    } else {
+        None
+    };
+    if let Some(direct_stream) = direct_stream {
+        if compression.is_some() {
+            return Err(compression_unsupported());
+        }
+        for (option, is_set) in [
+            ("follow", follow),
+            ("mode", mode.is_some()),
+            ("seekable", seekable.is_some()),
+        ] {
+            if is_set {
+                return Err(PyValueError::new_err(format!(
+                    "{option} is only supported for file-like objects, not strings, in-memory \
+                    buffers or iterables of chunks"
+                )));
+            }
+        }
+        return Ok(direct_stream);
+    }
+    make_file_like_stream(
+        stream,
+        buffering,
+        correct_cursor,
+        follow,
+        compression,
+        mode,
+        seekable,
+        prefetch,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn make_file_like_stream(
+    stream: Py<PyAny>,
+    buffering: BufferingMode,
+    correct_cursor: bool,
+    follow: bool,
+    compression: Option<(CompressionMode, SharedCompressionOffsets)>,
+    mode: Option<StreamMode>,
+    seekable: Option<bool>,
+    prefetch: bool,
+) -> PyResult<StreamAndSource> {
+    let source = Some(Python::attach(|py| stream.clone_ref(py)));
+    let read_return_type: ReadReturnType = match mode {
+        Some(StreamMode::Text) => ReadReturnType::String,
+        Some(StreamMode::Bytes) => ReadReturnType::Bytes,
+        None => determine_read_return_type(&stream)?,
+    };
+    let seekable: bool = match seekable {
+        Some(seekable) => seekable,
+        None => is_seekable(&stream)?,
+    };
+    if seekable && !has_seek_method(&stream)? {
+        return Err(PyTypeError::new_err(
+            "seekable streams must have a seek() method (in addition to read())",
+        ));
+    }
+    // only binary streams' peek() methods are useful to us (text streams don't have one anyway)
+    let peekable = matches!(read_return_type, ReadReturnType::Bytes) && is_peekable(&stream)?;
+    let stream_settings = decide_stream_settings(correct_cursor, buffering, seekable, peekable)?;
+    // unbuffered reading is meant to keep the Python-visible position up to date at all times,
+    // which reading from the file descriptor wouldn't do
+    let bypassable = matches!(read_return_type, ReadReturnType::Bytes)
+        && stream_settings.buffer_size().is_some();
+    if bypassable {
+        if let Some(fd_reader) = make_fd_reader(&stream, seekable)? {
+            // (see `PrefetchStream` for why unseekable files aren't supported)
+            #[cfg(unix)]
+            if let (true, Some(bufsize), Some(_)) =
+                (prefetch, stream_settings.buffer_size(), fd_reader.pos())
+            {
+                return Ok(StreamAndSource {
+                    stream: Box::new(PrefetchStream::new(fd_reader, bufsize)?),
+                    source,
+                    reads_without_gil: true,
+                });
+            }
+            let fd_stream = match compression {
+                Some((mode, offsets)) => {
+                    make_decompressing_stream(fd_reader, stream_settings, mode, offsets)?
+                }
+                None => make_bytes_stream(fd_reader, stream_settings, follow),
+            };
+            return Ok(StreamAndSource {
+                stream: fd_stream,
+                source,
+                // file descriptors are read from with the GIL released anyway
+                reads_without_gil: true,
+            });
+        }
+    }
+    let suitable_stream: Box<dyn SuitableStream + Send + Sync> = match read_return_type {
+        ReadReturnType::String if compression.is_some() => return Err(compression_unsupported()),
         ReadReturnType::String => {
             let py_text_stream = PyTextStream::new(stream);
             match stream_settings {
-                StreamSettings::Unbuffered => {
+                // (peekable is never true for text streams)
+                StreamSettings::Unbuffered | StreamSettings::PeekableBuffered => {
                     Box::new(SuitableUnbufferedTextStream::new(py_text_stream))
                 }
                 StreamSettings::UnseekableBuffered(bufsize) => Box::new(
@@ -118,18 +463,16 @@ pub fn make_suitable_stream(
                 ),
             }
         }
+        ReadReturnType::Bytes if stream_settings == StreamSettings::PeekableBuffered => {
+            Box::new(SuitablePeekableBufferedBytesStream::new(stream))
+        }
         ReadReturnType::Bytes => {
             let py_bytes_stream = PyBytesStream::new(stream);
-            match stream_settings {
-                StreamSettings::Unbuffered => {
-                    Box::new(SuitableUnbufferedBytesStream::new(py_bytes_stream))
+            match compression {
+                Some((mode, offsets)) => {
+                    make_decompressing_stream(py_bytes_stream, stream_settings, mode, offsets)?
                 }
-                StreamSettings::UnseekableBuffered(bufsize) => Box::new(
-                    SuitableUnseekableBufferedBytesStream::new(py_bytes_stream, bufsize),
-                ),
-                StreamSettings::SeekableBuffered(bufsize) => Box::new(
-                    SuitableSeekableBufferedBytesStream::new(py_bytes_stream, bufsize),
-                ),
+                None => make_bytes_stream(py_bytes_stream, stream_settings, follow),
             }
         }
         ReadReturnType::Other(t) => {
@@ -138,6 +481,11 @@ pub fn make_suitable_stream(
                 t
             )))
         }
+    };
+    Ok(StreamAndSource {
+        stream: suitable_stream,
+        source,
+        reads_without_gil: false,
     })
 }
 
@@ -151,64 +499,161 @@ mod tests {
         true,
         BufferingMode::BufferedWithSize(5),
         true,
-        Ok(StreamSettings::SeekableBuffered(5))
+        false,
+        Ok(StreamSettings::SeekableBuffered(BufferSize::fixed(5)))
     )]
     #[case(
         false,
         BufferingMode::BufferedWithSize(5),
         true,
-        Ok(StreamSettings::UnseekableBuffered(5))
+        false,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::fixed(5)))
+    )]
+    #[case(
+        true,
+        BufferingMode::Unbuffered,
+        true,
+        false,
+        Ok(StreamSettings::Unbuffered)
+    )]
+    #[case(
+        false,
+        BufferingMode::Unbuffered,
+        true,
+        false,
+        Ok(StreamSettings::Unbuffered)
     )]
-    #[case(true, BufferingMode::Unbuffered, true, Ok(StreamSettings::Unbuffered))]
-    #[case(false, BufferingMode::Unbuffered, true, Ok(StreamSettings::Unbuffered))]
     #[case(
         true,
         BufferingMode::BufferedWithSize(5),
         false,
-        Err(PyValueError::new_err(format!(
+        false,
+        Err(PyValueError::new_err(
             "Incompatible stream requirements: correct_cursor and a buffer size > 1 \
-            are only possible if the given stream is seekable, which this one is not"
-        )))
+            are only possible if the given stream is seekable or supports peek() and \
+            read1(), which this one does not"
+        ))
     )]
     #[case(
         false,
         BufferingMode::BufferedWithSize(5),
         false,
-        Ok(StreamSettings::UnseekableBuffered(5))
+        false,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::fixed(5)))
+    )]
+    #[case(
+        true,
+        BufferingMode::Unbuffered,
+        false,
+        false,
+        Ok(StreamSettings::Unbuffered)
     )]
-    #[case(true, BufferingMode::Unbuffered, false, Ok(StreamSettings::Unbuffered))]
     #[case(
         false,
         BufferingMode::Unbuffered,
         false,
+        false,
         Ok(StreamSettings::Unbuffered)
     )]
     #[case(
         true,
         BufferingMode::DontCare,
         true,
-        Ok(StreamSettings::SeekableBuffered(DEFAULT_BUFSIZE))
+        false,
+        Ok(StreamSettings::SeekableBuffered(BufferSize::fixed(DEFAULT_BUFSIZE)))
     )]
     #[case(
         false,
         BufferingMode::DontCare,
         true,
-        Ok(StreamSettings::UnseekableBuffered(DEFAULT_BUFSIZE))
+        false,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::fixed(DEFAULT_BUFSIZE)))
+    )]
+    #[case(
+        true,
+        BufferingMode::DontCare,
+        false,
+        false,
+        Ok(StreamSettings::Unbuffered)
+    )]
+    #[case(
+        false,
+        BufferingMode::DontCare,
+        false,
+        false,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::fixed(DEFAULT_BUFSIZE)))
+    )]
+    #[case(
+        true,
+        BufferingMode::DontCare,
+        false,
+        true,
+        Ok(StreamSettings::PeekableBuffered)
     )]
-    #[case(true, BufferingMode::DontCare, false, Ok(StreamSettings::Unbuffered))]
     #[case(
+        true,
+        BufferingMode::BufferedWithSize(5),
         false,
+        true,
+        Ok(StreamSettings::PeekableBuffered)
+    )]
+    #[case(
+        false,
+        BufferingMode::DontCare,
+        false,
+        true,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::fixed(DEFAULT_BUFSIZE)))
+    )]
+    #[case(
+        true,
         BufferingMode::DontCare,
+        true,
+        true,
+        Ok(StreamSettings::SeekableBuffered(BufferSize::fixed(DEFAULT_BUFSIZE)))
+    )]
+    #[case(
+        true,
+        BufferingMode::Unbuffered,
         false,
-        Ok(StreamSettings::UnseekableBuffered(DEFAULT_BUFSIZE))
+        true,
+        Ok(StreamSettings::Unbuffered)
+    )]
+    #[case(
+        false,
+        BufferingMode::Adaptive,
+        true,
+        false,
+        Ok(StreamSettings::UnseekableBuffered(BufferSize::adaptive()))
+    )]
+    #[case(
+        true,
+        BufferingMode::Adaptive,
+        true,
+        false,
+        Ok(StreamSettings::SeekableBuffered(BufferSize::adaptive()))
+    )]
+    #[case(
+        true,
+        BufferingMode::Adaptive,
+        false,
+        true,
+        Ok(StreamSettings::PeekableBuffered)
+    )]
+    #[case(
+        true,
+        BufferingMode::Adaptive,
+        false,
+        false,
+        Ok(StreamSettings::Unbuffered)
     )]
     fn test_decide_stream_settings(
         #[case] correct_cursor: bool,
         #[case] buffering: BufferingMode,
         #[case] seekable: bool,
+        #[case] peekable: bool,
         #[case] expected_result: PyResult<StreamSettings>,
     ) {
-        let result = decide_stream_settings(correct_cursor, buffering, seekable);
+        let result = decide_stream_settings(correct_cursor, buffering, seekable, peekable);
         if let (Ok(result_val), Ok(expected_result_val)) = (&result, &expected_result) {
             assert_eq!(result_val, expected_result_val);
         } else {
@@ -216,7 +661,7 @@ mod tests {
                 //assert_eq!(result_val.to_string(), expected_result_val.to_string());
                 //TODO ^ doesn't work because to_string() / Display requires Python interp...
             } else {
-                assert!(false, "expected {:?}, got {:?}", expected_result, result);
+                panic!("expected {:?}, got {:?}", expected_result, result);
             }
         }
     }
@@ -1,73 +1,95 @@
 use crate::park_cursor::ParkCursorChars;
-use crate::py_bytes_stream::PyBytesStream;
 use crate::remainder::{Remainder, StreamData};
 use crate::utf8_char_source::Utf8CharSource;
 use std::io;
 use std::io::Read;
 use utf8_width::get_width;
 
-/// Python bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
+/// Bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
 ///
 /// This means that the necessary traits (see below) are implemented for it.
 ///
-/// This is the variant for unseekable streams. Chars are read in from Python one-by-one, which is
-/// very slow but prevents readahead buffering.
-pub struct SuitableUnbufferedBytesStream {
-    inner: PyBytesStream,
+/// This is the variant for unseekable streams. Chars are read in from the underlying stream
+/// one-by-one, which is very slow but prevents readahead buffering.
+pub struct SuitableUnbufferedBytesStream<R: Read> {
+    inner: R,
+    // bytes of a char whose remaining bytes weren't available yet (non-blocking streams)
+    pending: Vec<u8>,
+    follow: bool,
 }
 
-impl SuitableUnbufferedBytesStream {
-    pub fn new(inner: PyBytesStream) -> Self {
-        SuitableUnbufferedBytesStream { inner }
+impl<R: Read> SuitableUnbufferedBytesStream<R> {
+    /// If `follow` is set, EOF is not considered final (see `FollowStream`).
+    pub fn new(inner: R, follow: bool) -> Self {
+        SuitableUnbufferedBytesStream {
+            inner,
+            pending: Vec::with_capacity(4),
+            follow,
+        }
     }
 }
 
-impl Utf8CharSource for SuitableUnbufferedBytesStream {
+impl<R: Read> Utf8CharSource for SuitableUnbufferedBytesStream<R> {
     fn read_char(&mut self) -> io::Result<Option<char>> {
         let mut buf: [u8; 4] = [0; 4];
-        let mut n_bytes_read = self.inner.read(&mut buf[..1])?;
-        if n_bytes_read < 1 {
-            // EOF
-            return Ok(None);
-        }
-        if n_bytes_read > 1 {
-            return Err(io::Error::other(
-                "broken stream: returns more bytes than requested",
-            ));
+        if self.pending.is_empty() {
+            let n_bytes_read = self.inner.read(&mut buf[..1])?;
+            if n_bytes_read < 1 {
+                // EOF
+                return Ok(None);
+            }
+            if n_bytes_read > 1 {
+                return Err(io::Error::other(
+                    "broken stream: returns more bytes than requested",
+                ));
+            }
+            self.pending.push(buf[0]);
         }
         // try to see if we're at the start of a unicode char:
-        let n_bytes_in_char = get_width(buf[0]);
+        let n_bytes_in_char = get_width(self.pending[0]);
         if n_bytes_in_char == 0 {
+            let start_byte = self.pending.remove(0);
             return Err(io::Error::other(format!(
                 "invalid UTF-8 start byte: {:x}",
-                buf[0]
+                start_byte
             )));
         }
         // if we're inside a unicode char, we try and read its remaining bytes
-        // (or until EOF, in which case from_utf8 below will return an error):
-        while n_bytes_read < n_bytes_in_char {
-            let n_bytes_read_cur = self.inner.read(&mut buf[n_bytes_read..n_bytes_in_char])?;
+        // (or until EOF, in which case from_utf8 below will return an error). if they're not
+        // available yet, the bytes read so far are kept for the next call:
+        while self.pending.len() < n_bytes_in_char {
+            let n_bytes_read_cur = self
+                .inner
+                .read(&mut buf[..n_bytes_in_char - self.pending.len()])?;
             if n_bytes_read_cur < 1 {
+                if self.follow {
+                    // the rest of the char might still be written
+                    return Err(io::Error::new(
+                        io::ErrorKind::WouldBlock,
+                        "incomplete UTF-8 sequence at EOF",
+                    ));
+                }
                 break; // EOF
             }
-            n_bytes_read += n_bytes_read_cur;
+            self.pending.extend_from_slice(&buf[..n_bytes_read_cur]);
         }
-        Ok(std::str::from_utf8(&buf[..n_bytes_read])
+        let bytes = std::mem::take(&mut self.pending);
+        Ok(std::str::from_utf8(&bytes)
             .map_err(|e| io::Error::other(format!("{}", e)))?
             .chars()
             .next())
     }
 }
 
-impl ParkCursorChars for SuitableUnbufferedBytesStream {
+impl<R: Read> ParkCursorChars for SuitableUnbufferedBytesStream<R> {
     fn park_cursor(&mut self) -> io::Result<()> {
         // no-op
         Ok(())
     }
 }
 
-impl Remainder for SuitableUnbufferedBytesStream {
+impl<R: Read> Remainder for SuitableUnbufferedBytesStream<R> {
     fn remainder(&self) -> StreamData {
-        StreamData::Bytes(vec![0; 0])
+        StreamData::Bytes(self.pending.clone())
     }
 }
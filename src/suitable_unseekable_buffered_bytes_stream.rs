@@ -1,53 +1,45 @@
+use crate::buffer_size::{BufferSize, SizeLimitedReader};
 use crate::park_cursor::ParkCursorChars;
-use crate::py_bytes_stream::PyBytesStream;
 use crate::remainder::{Remainder, StreamData};
 use crate::utf8_char_source::Utf8CharSource;
+use crate::utf8_reader::read_char;
 use std::io;
-use utf8_read::{Char, Reader};
+use std::io::Read;
+use utf8_read::Reader;
 
-/// Python unseekable bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
+/// Unseekable bytes stream wrapper that makes it "suitable" for use in the Tokenizer.
 ///
 /// This means that the necessary traits (see below) are implemented for it.
-pub struct SuitableUnseekableBufferedBytesStream {
+pub struct SuitableUnseekableBufferedBytesStream<R: Read> {
     // note that this is not actually optional, it's just a shitty hack because I'm too dumb to
     // placate Rust when temporarily moving the reader out of the struct within a method...
-    reader: Option<Reader<PyBytesStream>>,
+    reader: Option<Reader<SizeLimitedReader<R>>>,
 }
 
-impl SuitableUnseekableBufferedBytesStream {
-    pub fn new(inner: PyBytesStream, bufsize: usize) -> Self {
+impl<R: Read> SuitableUnseekableBufferedBytesStream<R> {
+    /// If `follow` is set, EOF is not considered final (see `FollowStream`).
+    pub fn new(inner: R, bufsize: BufferSize, follow: bool) -> Self {
+        let inner = SizeLimitedReader::new(inner, bufsize);
         SuitableUnseekableBufferedBytesStream {
-            reader: Some(Reader::with_chunk_size(inner, bufsize)),
+            reader: Some(Reader::with_chunk_size(inner, bufsize.max()).set_eof_on_no_data(!follow)),
         }
     }
 }
 
-impl Utf8CharSource for SuitableUnseekableBufferedBytesStream {
+impl<R: Read> Utf8CharSource for SuitableUnseekableBufferedBytesStream<R> {
     fn read_char(&mut self) -> io::Result<Option<char>> {
-        Ok(
-            match self
-                .reader
-                .as_mut()
-                .unwrap()
-                .next_char()
-                .map_err(|e| io::Error::other(format!("{}", e)))?
-            {
-                Char::Eof => None,
-                Char::Char(c) => Some(c),
-                Char::NoData => None, // for us this means the same as EOF I guess?
-            },
-        )
+        read_char(self.reader.as_mut().unwrap())
     }
 }
 
-impl ParkCursorChars for SuitableUnseekableBufferedBytesStream {
+impl<R: Read> ParkCursorChars for SuitableUnseekableBufferedBytesStream<R> {
     fn park_cursor(&mut self) -> io::Result<()> {
         // no-op
         Ok(())
     }
 }
 
-impl Remainder for SuitableUnseekableBufferedBytesStream {
+impl<R: Read> Remainder for SuitableUnseekableBufferedBytesStream<R> {
     fn remainder(&self) -> StreamData {
         StreamData::Bytes(match &self.reader {
             Some(reader) => reader.borrow_buffer().to_owned(),
@@ -1,3 +1,4 @@
+use crate::buffer_size::BufferSize;
 use crate::park_cursor::ParkCursorChars;
 use crate::py_text_stream::PyTextStream;
 use crate::read_string::ReadString;
@@ -12,13 +13,13 @@ use std::iter::Iterator;
 /// This means that the necessary traits (see below) are implemented for it.
 pub struct SuitableUnseekableBufferedTextStream {
     inner: PyTextStream,
-    buffer_size: usize,
+    buffer_size: BufferSize,
     chars_iter: OwnedChars,
     chars_read_from_buf: usize,
 }
 
 impl SuitableUnseekableBufferedTextStream {
-    pub fn new(inner: PyTextStream, buffer_size: usize) -> Self {
+    pub fn new(inner: PyTextStream, buffer_size: BufferSize) -> Self {
         SuitableUnseekableBufferedTextStream {
             inner,
             buffer_size,
@@ -34,7 +35,7 @@ impl Utf8CharSource for SuitableUnseekableBufferedTextStream {
             self.chars_read_from_buf += 1;
             Ok(Some(c))
         } else {
-            let buf = self.inner.read_string(self.buffer_size)?;
+            let buf = self.inner.read_string(self.buffer_size.next_size())?;
             self.chars_iter = OwnedCharsExt::into_chars(buf);
             self.chars_read_from_buf = 0;
             let oc = self.chars_iter.next();
@@ -0,0 +1,63 @@
+/// Options shared by all ways of creating a tokenizer.
+use crate::record_format::RecordFormat;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Options concerning the tokenization itself rather than the source of the data.
+///
+/// The constructor and all alternative constructors take these as `**options` instead of listing
+/// them in their signatures, so adding a new one only requires changing this struct and
+/// `from_kwargs`.
+#[derive(Default)]
+pub struct TokenizerOptions {
+    pub record_format: Option<RecordFormat>,
+    pub recover: bool,
+    pub emit_error_tokens: bool,
+    pub scan: bool,
+    pub allow_partial: bool,
+}
+
+impl TokenizerOptions {
+    /// Parse the options from the keyword arguments not consumed by a constructor's signature.
+    pub fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut options = TokenizerOptions::default();
+        let mut ndjson = false;
+        let mut json_seq = false;
+        for (key, value) in kwargs.into_iter().flatten() {
+            let key = key.extract::<String>()?;
+            let field = match key.as_str() {
+                "ndjson" => &mut ndjson,
+                "json_seq" => &mut json_seq,
+                "recover" => &mut options.recover,
+                "emit_error_tokens" => &mut options.emit_error_tokens,
+                "scan" => &mut options.scan,
+                "allow_partial" => &mut options.allow_partial,
+                _ => {
+                    return Err(PyTypeError::new_err(format!(
+                        "got an unexpected keyword argument '{key}'"
+                    )))
+                }
+            };
+            *field = value.extract().map_err(|e: PyErr| {
+                PyTypeError::new_err(format!("argument '{key}': {}", e.value(value.py())))
+            })?;
+        }
+        options.record_format = match (ndjson, json_seq) {
+            (false, false) => None,
+            (true, false) => Some(RecordFormat::Ndjson),
+            (false, true) => Some(RecordFormat::JsonSeq),
+            (true, true) => {
+                return Err(PyValueError::new_err(
+                    "ndjson and json_seq are mutually exclusive",
+                ))
+            }
+        };
+        if options.scan && options.record_format.is_some() {
+            return Err(PyValueError::new_err(
+                "scan is mutually exclusive with ndjson and json_seq",
+            ));
+        }
+        Ok(options)
+    }
+}
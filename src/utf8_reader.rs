@@ -0,0 +1,44 @@
+/// Utilities for reading chars via `utf8_read`'s `Reader` or from byte slices.
+use std::io;
+use std::io::Read;
+use utf8_read::{Char, Error, Reader};
+use utf8_width::get_width;
+
+/// Read a char from a `Reader` the way `Utf8CharSource::read_char` would.
+///
+/// The absence of data on non-blocking streams is reported as an error of kind `WouldBlock`
+/// instead of being mistaken for EOF.
+pub fn read_char<R: Read>(reader: &mut Reader<R>) -> io::Result<Option<char>> {
+    match reader.next_char() {
+        Ok(Char::Eof) => Ok(None),
+        Ok(Char::Char(c)) => Ok(Some(c)),
+        Ok(Char::NoData) => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "no data available yet",
+        )),
+        Err(Error::IoError(e)) if e.kind() == io::ErrorKind::WouldBlock => Err(e),
+        Err(e) => Err(io::Error::other(format!("{}", e))),
+    }
+}
+
+/// Read the char starting at position `pos` of a byte slice, advancing `pos` past it.
+pub fn read_char_from_slice(data: &[u8], pos: &mut usize) -> io::Result<Option<char>> {
+    let data = &data[*pos..];
+    let Some(&start_byte) = data.first() else {
+        return Ok(None); // EOF
+    };
+    let n_bytes_in_char = get_width(start_byte);
+    if n_bytes_in_char == 0 {
+        return Err(io::Error::other(format!(
+            "invalid UTF-8 start byte: {:x}",
+            start_byte
+        )));
+    }
+    let bytes = &data[..n_bytes_in_char.min(data.len())];
+    let c = std::str::from_utf8(bytes)
+        .map_err(|e| io::Error::other(format!("{}", e)))?
+        .chars()
+        .next();
+    *pos += bytes.len();
+    Ok(c)
+}
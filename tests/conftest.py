@@ -1,7 +1,20 @@
+import gzip
+import os
+import pathlib
 from io import SEEK_CUR, SEEK_END, BytesIO, StringIO
 
 import pytest
 
+from json_stream_rs_tokenizer import RustTokenizer
+
+# document with tokens of all types and multi-byte chars, along with its
+# tokens' values, for tests that read it from various sources
+DATA = '{"a": [1, 2.5, "bä€\U0001f600"], "b": null}'
+TOKENS = [
+    "{", "a", ":", "[", 1, ",", 2.5, ",", "bä€\U0001f600", "]", ",",
+    "b", ":", None, "}",
+]
+
 
 class StringIOWithLargeCursorPositions(StringIO):
     """
@@ -79,3 +92,82 @@ def bytes_to_bytes_buf(request):
         return make_unseekable_bytesio
     else:
         assert False
+
+
+@pytest.fixture
+def file_path(tmp_path):
+    return str(tmp_path / "data.json")
+
+
+def write_file(path, data):
+    with open(path, "wb") as f:
+        f.write(data.encode("utf-8"))
+
+
+@pytest.fixture(
+    params=[
+        "bytes",
+        "bytearray",
+        "memoryview",
+        "memoryview-slice",
+        "readinto",
+        "file",
+        "file-unbuffered",
+        "path",
+        "pathlib-path",
+        "gzip",
+        "gzip-file",
+        "prefetch",
+    ]
+)
+def tokenize_from_source(request, file_path):
+    """
+    Provides function that takes a str and returns the values of the tokens
+    obtained by tokenizing it from some source other than a text stream.
+
+    Causes dependent test to be repeated for all kinds of such sources that
+    are read without going through the source's read() method (buffers, file
+    descriptors, paths, ...) or that are decompressed.
+    """
+    if request.param == "prefetch" and os.name != "posix":
+        pytest.skip("prefetch is only supported on Unix")
+
+    def tokenize(data: str):
+        b = data.encode("utf-8")
+        if request.param == "bytes":
+            tokenizer = RustTokenizer(b)
+        elif request.param == "bytearray":
+            tokenizer = RustTokenizer(bytearray(b))
+        elif request.param == "memoryview":
+            tokenizer = RustTokenizer(memoryview(b))
+        elif request.param == "memoryview-slice":
+            tokenizer = RustTokenizer(memoryview(b"xx" + b)[2:])
+        elif request.param == "readinto":
+            tokenizer = RustTokenizer(BytesIO(b))
+        elif request.param == "gzip":
+            tokenizer = RustTokenizer(
+                BytesIO(gzip.compress(b)), compression="auto"
+            )
+        elif request.param in ("path", "pathlib-path"):
+            write_file(file_path, data)
+            path = (
+                pathlib.Path(file_path)
+                if request.param == "pathlib-path"
+                else file_path
+            )
+            return [val for kind, val in RustTokenizer.from_path(path)]
+        else:
+            compressed = request.param == "gzip-file"
+            with open(file_path, "wb") as f:
+                f.write(gzip.compress(b) if compressed else b)
+            buffering = 0 if request.param == "file-unbuffered" else -1
+            with open(file_path, "rb", buffering=buffering) as f:
+                tokenizer = RustTokenizer(
+                    f,
+                    compression="auto" if compressed else None,
+                    prefetch=request.param == "prefetch",
+                )
+                return [val for kind, val in tokenizer]
+        return [val for kind, val in tokenizer]
+
+    return tokenize
@@ -0,0 +1,65 @@
+"""
+Tests for tokenizing JSON that is cut off by the end of the stream.
+"""
+import math
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer, TokenType
+
+
+def tokens_and_partial_flags(tokenizer):
+    return [(kind, val, tokenizer.partial) for kind, val in tokenizer]
+
+
+@pytest.mark.parametrize(
+    "s,expected_last_token",
+    [
+        ('["ab', (TokenType.String_, "ab")),
+        ('["a\\', (TokenType.String_, "a")),
+        ('["a\\u00', (TokenType.String_, "a")),
+        ("[12", (TokenType.Number, 12)),
+        ("[-", None),
+        ("[1.", (TokenType.Number, 1)),
+        ("[1.5", (TokenType.Number, 1.5)),
+        ("[1.5e-", (TokenType.Number, 1.5)),
+        ("[2e3", (TokenType.Number, 2000.0)),
+        ("[tr", (TokenType.Boolean, True)),
+        ("[fals", (TokenType.Boolean, False)),
+        ("[n", (TokenType.Null, None)),
+        ("[-Inf", (TokenType.Number, -math.inf)),
+    ],
+)
+def test_partial_tokens(s, expected_last_token, to_bytes_or_str_buf):
+    tokenizer = RustTokenizer(to_bytes_or_str_buf(s), allow_partial=True)
+    tokens = tokens_and_partial_flags(tokenizer)
+    assert tokens[0] == (TokenType.Operator, "[", False)
+    if expected_last_token is None:
+        assert len(tokens) == 1
+    else:
+        assert tokens[1:] == [(*expected_last_token, True)]
+
+
+def test_complete_tokens_not_partial(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": [1, true, "b"')
+    tokenizer = RustTokenizer(buf, allow_partial=True)
+    tokens = tokens_and_partial_flags(tokenizer)
+    assert all(not partial for kind, val, partial in tokens)
+    assert tokenizer.open_containers() == ["{", "["]
+
+
+def test_open_containers(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": [{"b": "c')
+    tokenizer = RustTokenizer(buf, allow_partial=True)
+    assert tokenizer.open_containers() == []
+    open_containers = []
+    for kind, val in tokenizer:
+        open_containers.append(tokenizer.open_containers())
+    assert open_containers[-1] == ["{", "[", "{"]
+    assert open_containers[:3] == [["{"], ["{"], ["{"]]
+
+
+def test_partial_disallowed_by_default(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('["ab')
+    with pytest.raises(ValueError, match="Unterminated string"):
+        list(RustTokenizer(buf))
@@ -0,0 +1,81 @@
+"""
+Tests for the asynchronous tokenizer.
+"""
+import asyncio
+
+import pytest
+
+from json_stream_rs_tokenizer import AsyncRustTokenizer, TokenType
+
+
+class AsyncChunkReader:
+    """
+    Minimal object with an awaitable read(n), returning the given data.
+    """
+
+    def __init__(self, data):
+        self.data = data
+        self.read_sizes = []
+
+    async def read(self, n):
+        self.read_sizes.append(n)
+        await asyncio.sleep(0)
+        chunk, self.data = self.data[:n], self.data[n:]
+        return chunk
+
+
+async def collect(tokenizer):
+    return [token async for token in tokenizer]
+
+
+@pytest.mark.parametrize("data_type", [str, bytes])
+@pytest.mark.parametrize("buffering", [-1, 0, 3])
+def test_async_tokenizer(data_type, buffering):
+    s = '{"a": [1, 2.5, true, null, "bä"]} 3'
+    data = s if data_type is str else s.encode("utf-8")
+    tokenizer = AsyncRustTokenizer(AsyncChunkReader(data), buffering=buffering)
+    assert asyncio.run(collect(tokenizer)) == [
+        (TokenType.Operator, "{"),
+        (TokenType.String_, "a"),
+        (TokenType.Operator, ":"),
+        (TokenType.Operator, "["),
+        (TokenType.Number, 1),
+        (TokenType.Operator, ","),
+        (TokenType.Number, 2.5),
+        (TokenType.Operator, ","),
+        (TokenType.Boolean, True),
+        (TokenType.Operator, ","),
+        (TokenType.Null, None),
+        (TokenType.Operator, ","),
+        (TokenType.String_, "bä"),
+        (TokenType.Operator, "]"),
+        (TokenType.Operator, "}"),
+        (TokenType.Number, 3),
+    ]
+
+
+def test_async_tokenizer_buffering():
+    reader = AsyncChunkReader(b"[1, 2]")
+    asyncio.run(collect(AsyncRustTokenizer(reader, buffering=0)))
+    assert set(reader.read_sizes) == {1}
+
+
+def test_async_tokenizer_stream_reader():
+    async def main():
+        reader = asyncio.StreamReader()
+        tokenizer = AsyncRustTokenizer(reader, ndjson=True)
+
+        async def write():
+            for chunk in [b'{"a"', b": 1}\n", b"[2]\n"]:
+                reader.feed_data(chunk)
+                await asyncio.sleep(0)
+            reader.feed_eof()
+
+        writer = asyncio.ensure_future(write())
+        tokens = await collect(tokenizer)
+        await writer
+        return tokens
+
+    assert [val for kind, val in asyncio.run(main())] == [
+        "{", "a", ":", 1, "}", 0, "[", 2, "]", 1,
+    ]
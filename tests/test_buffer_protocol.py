@@ -0,0 +1,59 @@
+"""
+Tests for tokenizing objects supporting the buffer protocol in place.
+"""
+import mmap
+import tempfile
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+def test_buffer_remainder():
+    tokenizer = RustTokenizer(b'{"a": 1} {"b": 2}')
+    for kind, val in tokenizer:
+        if val == "}":
+            break
+    assert tokenizer.remainder == b' {"b": 2}'
+
+
+def test_mutable_buffer_is_copied():
+    data = bytearray(b"[1, 2]")
+    tokenizer = RustTokenizer(data)
+    next(tokenizer)
+    data[1:2] = b"3"
+    data.extend(b"[4]")
+    assert [val for kind, val in tokenizer] == [1, ",", 2, "]"]
+
+
+def test_mmap_park_cursor():
+    with tempfile.TemporaryFile() as f:
+        f.write(b'{"a": 1} {"b": 2}')
+        f.flush()
+        with mmap.mmap(f.fileno(), 0, access=mmap.ACCESS_READ) as m:
+            tokenizer = RustTokenizer(m, correct_cursor=True)
+            for kind, val in tokenizer:
+                if val == "}":
+                    break
+            tokenizer.park_cursor()
+            assert m.tell() == 8
+            del tokenizer
+
+
+@pytest.mark.parametrize(
+    "data,match",
+    [(b'["\xff"]', "invalid UTF-8"), (b'["\xe2\x82', "incomplete utf-8")],
+)
+def test_buffer_invalid_utf8(data, match):
+    with pytest.raises(OSError, match=match):
+        list(RustTokenizer(data))
+
+
+def test_buffer_non_contiguous():
+    with pytest.raises(TypeError, match="contiguous"):
+        RustTokenizer(memoryview(b"[1, 2]")[::2])
+
+
+def test_buffer_incompatible_format():
+    with pytest.raises(BufferError):
+        RustTokenizer(memoryview(b"[1, 2]").cast("h", (3,)))
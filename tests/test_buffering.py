@@ -90,3 +90,64 @@ def test_buffering_cursor_pos_with_correct_cursor_enforcement(
         assert buf.tell() == 10
     else:
         assert False, "what"
+
+
+class RecordingStringIO(StringIO):
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.read_sizes = []
+
+    def read(self, size=-1):
+        self.read_sizes.append(size)
+        return super().read(size)
+
+
+class RecordingBytesIO(BytesIO):
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.read_sizes = []
+
+    def readinto(self, b):
+        self.read_sizes.append(len(b))
+        return super().readinto(b)
+
+
+ADAPTIVE_JSON = '{"a": [' + ", ".join(f'"ä{i}"' for i in range(20000)) + "]}"
+
+
+@pytest.mark.parametrize("correct_cursor", [False, True])
+def test_adaptive_buffering(correct_cursor, to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(ADAPTIVE_JSON + ' {"b": 2}')
+    tokenizer = RustTokenizer(
+        buf, buffering="adaptive", correct_cursor=correct_cursor
+    )
+    tokens = []
+    for token in tokenizer:
+        tokens.append(token)
+        if token == (0, "}"):
+            break
+    assert len(tokens) == 2 * 20000 + 5
+    assert tokens[-3] == (1, "ä19999")
+    if correct_cursor:
+        tokenizer.park_cursor()
+        rest = buf.read()
+        assert rest in (' {"b": 2}', b' {"b": 2}')
+
+
+@pytest.mark.parametrize("recording_io", [RecordingStringIO, RecordingBytesIO])
+def test_adaptive_buffering_grows(recording_io):
+    data = ADAPTIVE_JSON
+    if recording_io is RecordingBytesIO:
+        data = data.encode("utf-8")
+    buf = recording_io(data)
+    list(RustTokenizer(buf, buffering="adaptive"))
+    sizes = [size for size in buf.read_sizes if size != 0]
+    assert sizes[0] <= 1024
+    assert sizes == sorted(sizes)
+    assert sizes[-1] > 8000
+    assert len(sizes) < 40
+
+
+def test_adaptive_buffering_invalid_strategy():
+    with pytest.raises(ValueError, match="adaptive"):
+        RustTokenizer(StringIO("[]"), buffering="fast")
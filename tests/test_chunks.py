@@ -0,0 +1,97 @@
+"""
+Tests for reading from iterables of chunks instead of file-like objects.
+"""
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+def chunked(data, size):
+    return (data[i : i + size] for i in range(0, len(data), size))
+
+
+@pytest.mark.parametrize("size", [1, 2, 3, 100])
+@pytest.mark.parametrize("as_bytes", [False, True])
+def test_chunks(size, as_bytes):
+    data = '{"a": [1, 2.5, "bä€\U0001f600"], "b": null}'
+    if as_bytes:
+        data = data.encode("utf-8")
+    tokens = [val for kind, val in RustTokenizer(chunked(data, size))]
+    assert tokens == [
+        "{", "a", ":", "[", 1, ",", 2.5, ",", "bä€\U0001f600", "]", ",",
+        "b", ":", None, "}",
+    ]
+
+
+def test_chunks_from_list():
+    tokens = [val for kind, val in RustTokenizer(["[1, ", "", "2]"])]
+    assert tokens == ["[", 1, ",", 2, "]"]
+
+
+@pytest.mark.parametrize(
+    "chunks,remainder",
+    [
+        (["[1] [", "2]"], " ["),
+        ([b"[1] [\xc3", b"\xa4]"], b" [\xc3"),
+    ],
+)
+def test_chunks_remainder(chunks, remainder):
+    tokenizer = RustTokenizer(iter(chunks))
+    assert [val for kind, val in islice(tokenizer, 3)] == ["[", 1, "]"]
+    assert tokenizer.remainder == remainder
+
+
+def test_chunks_are_pulled_lazily():
+    pulled = []
+
+    def chunks():
+        for chunk in ["[1]", "[2]"]:
+            pulled.append(chunk)
+            yield chunk
+
+    tokenizer = RustTokenizer(chunks())
+    assert [val for kind, val in islice(tokenizer, 3)] == ["[", 1, "]"]
+    assert pulled == ["[1]"]
+
+
+def test_chunks_iterator_exception_passed_through():
+    class CustomError(Exception):
+        pass
+
+    def chunks():
+        yield "[1, "
+        raise CustomError("connection lost")
+
+    tokenizer = RustTokenizer(chunks())
+    with pytest.raises(CustomError, match="connection lost"):
+        list(tokenizer)
+
+
+@pytest.mark.parametrize(
+    "chunks,match",
+    [
+        (["[1, ", b"2]"], "bytes chunk after having got str chunks"),
+        ([b"[\xe2\x82"], "middle of a UTF-8 sequence"),
+        ([b"[\xff]"], "invalid UTF-8"),
+    ],
+)
+def test_chunks_invalid(chunks, match):
+    with pytest.raises(OSError, match=match):
+        list(RustTokenizer(chunks))
+
+
+def test_chunks_invalid_type():
+    with pytest.raises(TypeError, match="unsuitable chunk data type"):
+        list(RustTokenizer(["[", 1]))
+
+
+def test_chunks_follow_unsupported():
+    with pytest.raises(ValueError, match="follow"):
+        RustTokenizer(["[]"], follow=True)
+
+
+def test_not_iterable():
+    with pytest.raises(TypeError, match="iterable"):
+        RustTokenizer(1)
@@ -0,0 +1,128 @@
+"""
+Tests for tokenizing compressed streams.
+"""
+import bz2
+import gzip
+import lzma
+import os
+import threading
+from io import BytesIO, StringIO
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+from .conftest import DATA, TOKENS
+
+
+# Python only has a zstd module from 3.14 on, so this is '[1, 2]\n'
+# compressed via the zstd command line tool
+ZSTD_DATA = b"(\xb5/\xfd\x04X9\x00\x00[1, 2]\n[\x01\xb9\x1c"
+
+COMPRESSORS = {"gzip": gzip.compress, "bz2": bz2.compress, "xz": lzma.compress}
+
+
+@pytest.mark.parametrize("name", COMPRESSORS)
+@pytest.mark.parametrize("explicit", [False, True])
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_compression(name, explicit, buffering):
+    stream = BytesIO(COMPRESSORS[name](DATA.encode("utf-8")))
+    tokenizer = RustTokenizer(
+        stream,
+        buffering=buffering,
+        compression=name if explicit else "auto",
+    )
+    assert [val for kind, val in tokenizer] == TOKENS
+
+
+@pytest.mark.parametrize("explicit", [False, True])
+def test_compression_zstd(explicit):
+    tokenizer = RustTokenizer(
+        BytesIO(ZSTD_DATA), compression="zstd" if explicit else "auto"
+    )
+    assert [val for kind, val in tokenizer] == ["[", 1, ",", 2, "]"]
+
+
+@pytest.mark.parametrize("name", COMPRESSORS)
+def test_compression_multiple_members(name):
+    compress = COMPRESSORS[name]
+    stream = BytesIO(compress(b"[1] ") + compress(b"[2]"))
+    tokens = [val for kind, val in RustTokenizer(stream, compression=name)]
+    assert tokens == ["[", 1, "]", "[", 2, "]"]
+
+
+@pytest.mark.parametrize("data", ["[1, 2]", ""])
+def test_compression_auto_uncompressed(data):
+    stream = BytesIO(data.encode("utf-8"))
+    tokenizer = RustTokenizer(stream, compression="auto")
+    assert [val for kind, val in tokenizer] == (
+        ["[", 1, ",", 2, "]"] if data else []
+    )
+
+
+def test_compression_auto_doesnt_read_on_creation():
+    compressed = gzip.compress(b"[1, 2]")
+    read_fd, write_fd = os.pipe()
+    created = threading.Event()
+    created_before_data = []
+
+    def write():
+        created_before_data.append(created.wait(timeout=5))
+        with os.fdopen(write_fd, "wb") as f:
+            f.write(compressed)
+
+    thread = threading.Thread(target=write)
+    thread.start()
+    with os.fdopen(read_fd, "rb") as f:
+        tokenizer = RustTokenizer(f, compression="auto")
+        created.set()
+        assert tokenizer.compressed_offset == 0
+        assert [val for kind, val in tokenizer] == ["[", 1, ",", 2, "]"]
+    thread.join()
+    assert created_before_data == [True]
+
+
+def test_compression_offsets():
+    compressed = gzip.compress(b'{"a": 1} {"b": 2}')
+    tokenizer = RustTokenizer(BytesIO(compressed), compression="gzip")
+    assert [val for kind, val in islice(tokenizer, 5)] == [
+        "{", "a", ":", 1, "}",
+    ]
+    assert tokenizer.compressed_offset == len(compressed)
+    assert tokenizer.decompressed_offset == 8
+    assert tokenizer.remainder == b' {"b": 2}'
+
+
+def test_no_compression_offsets():
+    tokenizer = RustTokenizer(BytesIO(b"[]"))
+    assert tokenizer.compressed_offset is None
+    assert tokenizer.decompressed_offset is None
+
+
+def test_compression_invalid_data():
+    with pytest.raises(OSError):
+        list(RustTokenizer(BytesIO(b"[1, 2]"), compression="gzip"))
+
+
+def test_compression_unknown():
+    with pytest.raises(ValueError, match="unknown compression"):
+        RustTokenizer(BytesIO(b"[]"), compression="zip")
+
+
+@pytest.mark.parametrize(
+    "stream",
+    [gzip.compress(b"[]"), "[]", ["[]"], StringIO("[]")],
+    ids=["bytes", "str", "list", "text-stream"],
+)
+def test_compression_unsupported_stream(stream):
+    with pytest.raises(ValueError, match="binary file-like objects"):
+        RustTokenizer(stream, compression="gzip")
+
+
+@pytest.mark.parametrize(
+    "kwargs", [{"correct_cursor": True}, {"follow": True}]
+)
+def test_compression_incompatible_options(kwargs):
+    with pytest.raises(ValueError, match="can't be combined"):
+        RustTokenizer(BytesIO(b"[]"), compression="gzip", **kwargs)
@@ -0,0 +1,78 @@
+"""
+Tests for tokenizing streams made up of several concatenated documents.
+"""
+from io import BytesIO
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+def test_documents(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": 1} [2, "b"]\n3 "c"\n\n{"d": {"e": []}}\n')
+    tokenizer = RustTokenizer(buf)
+    documents = [
+        [val for kind, val in document] for document in tokenizer.documents()
+    ]
+    assert documents == [
+        ["{", "a", ":", 1, "}"],
+        ["[", 2, ",", "b", "]"],
+        [3],
+        ["c"],
+        ["{", "d", ":", "{", "e", ":", "[", "]", "}", "}"],
+    ]
+
+
+def test_documents_skips_unconsumed_tokens(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": [1, 2, 3]} {"b": 4}')
+    tokenizer = RustTokenizer(buf)
+    documents = tokenizer.documents()
+    first = next(documents)
+    assert next(first) == (0, "{")
+    second = next(documents)
+    assert list(first) == []  # invalidated by requesting the next document
+    assert [val for kind, val in second] == ["{", "b", ":", 4, "}"]
+    assert list(documents) == []
+
+
+@pytest.mark.parametrize("doc", ['{"a": [1, 2, 3]}', "[]", "12", '"s"'])
+def test_documents_skips_untouched_documents(doc, to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(f'{doc} {{"b": 4}} [5]')
+    documents = RustTokenizer(buf).documents()
+    next(documents)
+    second = next(documents)
+    assert [val for kind, val in second] == ["{", "b", ":", 4, "}"]
+    assert [val for kind, val in next(documents)] == ["[", 5, "]"]
+    assert list(documents) == []
+
+
+def test_documents_empty_stream(to_bytes_or_str_buf):
+    tokenizer = RustTokenizer(to_bytes_or_str_buf("  \n "))
+    assert list(tokenizer.documents()) == []
+
+
+def test_reset_keeps_buffered_data():
+    """
+    Test that reset() allows continuing after an invalid document.
+
+    Uses an unseekable stream with a buffer large enough to read everything
+    at once, so creating a new tokenizer instead would lose all subsequent
+    documents.
+    """
+    buf = BytesIO(b'{"a": tru} {"b": 2} {"c": 3}')
+    buf.seekable = lambda: False
+    tokenizer = RustTokenizer(buf, buffering=2000)
+    tokens = []
+    try:
+        for kind, val in tokenizer:
+            tokens.append(val)
+    except ValueError:
+        pass
+    else:
+        assert False, "expected error"
+    assert tokens == ["{", "a", ":"]
+    tokenizer.reset()
+    # the offending "}" has been consumed by the failed attempt
+    assert [val for kind, val in tokenizer] == [
+        "{", "b", ":", 2, "}", "{", "c", ":", 3, "}"
+    ]
@@ -0,0 +1,90 @@
+"""
+Tests for reading directly from the file descriptors of Python file objects.
+"""
+import io
+import os
+import threading
+import time
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+from .conftest import DATA, TOKENS, write_file
+
+
+@pytest.mark.parametrize("file_buffering", [0, -1])
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+@pytest.mark.parametrize("correct_cursor", [False, True])
+def test_file(file_path, file_buffering, buffering, correct_cursor):
+    write_file(file_path, DATA)
+    with open(file_path, "rb", buffering=file_buffering) as f:
+        tokenizer = RustTokenizer(
+            f, buffering=buffering, correct_cursor=correct_cursor
+        )
+        assert [val for kind, val in tokenizer] == TOKENS
+
+
+@pytest.mark.parametrize("mode", ["rb", "r+b"])
+def test_file_starts_at_logical_position(file_path, mode):
+    write_file(file_path, "xx [1, 2]")
+    with open(file_path, mode) as f:
+        # reads ahead into Python's buffer
+        assert f.read(3) == b"xx "
+        tokens = [val for kind, val in RustTokenizer(f)]
+    assert tokens == ["[", 1, ",", 2, "]"]
+
+
+def test_file_park_cursor(file_path):
+    write_file(file_path, '{"a": 1} {"b": 2}')
+    with open(file_path, "rb") as f:
+        tokenizer = RustTokenizer(f, correct_cursor=True)
+        assert [val for kind, val in islice(tokenizer, 5)] == [
+            "{", "a", ":", 1, "}",
+        ]
+        tokenizer.park_cursor()
+        assert f.tell() == 8
+        assert f.read() == b' {"b": 2}'
+
+
+def test_file_subclass_methods_are_used(file_path):
+    write_file(file_path, "[1, 2]")
+
+    class ReplacingReader(io.BufferedReader):
+        def readinto(self, b):
+            n = super().readinto(b)
+            b[:n] = bytes(b[:n]).replace(b"2", b"3")
+            return n
+
+    with ReplacingReader(io.FileIO(file_path)) as f:
+        tokens = [val for kind, val in RustTokenizer(f)]
+    assert tokens == ["[", 1, ",", 3, "]"]
+
+
+def test_pipe_with_python_buffered_data():
+    r, w = os.pipe()
+    with open(r, "rb") as rf:
+        os.write(w, b"xx [1, 2]")
+        os.close(w)
+        # Python reads as much as possible into its buffer here
+        assert rf.read(3) == b"xx "
+        tokens = [val for kind, val in RustTokenizer(rf)]
+    assert tokens == ["[", 1, ",", 2, "]"]
+
+
+def test_pipe_other_threads_can_run():
+    r, w = os.pipe()
+
+    def write():
+        for part in [b"[1, ", b"2]"]:
+            time.sleep(0.05)
+            os.write(w, part)
+        os.close(w)
+
+    thread = threading.Thread(target=write)
+    with open(r, "rb", buffering=0) as rf:
+        thread.start()
+        tokens = [val for kind, val in RustTokenizer(rf)]
+    thread.join()
+    assert tokens == ["[", 1, ",", 2, "]"]
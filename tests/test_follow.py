@@ -0,0 +1,135 @@
+"""
+Tests for following streams that are still being written to.
+"""
+import os
+import tempfile
+import threading
+import time
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+@pytest.fixture
+def growing_file():
+    fd, path = tempfile.mkstemp()
+    os.close(fd)
+
+    def append_later(parts, delay=0.05):
+        def append():
+            for part in parts:
+                time.sleep(delay)
+                with open(path, "ab") as f:
+                    f.write(part.encode("utf-8"))
+
+        thread = threading.Thread(target=append)
+        thread.start()
+        return thread
+
+    yield path, append_later
+    os.remove(path)
+
+
+@pytest.mark.parametrize("mode", ["r", "rb"])
+@pytest.mark.parametrize("buffering", [-1, 0])
+def test_follow(growing_file, mode, buffering):
+    path, append_later = growing_file
+    with open(path, "wb") as f:
+        f.write('{"a": 1}\n{"b": 2'.encode("utf-8"))
+    thread = append_later(["3, ", '"cä', '€"}\n'])
+    with open(path, mode) as f:
+        tokenizer = RustTokenizer(
+            f,
+            buffering=buffering,
+            ndjson=True,
+            follow=True,
+            poll_interval=0.01,
+            follow_timeout=0.5,
+        )
+        tokens = [val for kind, val in tokenizer]
+    thread.join()
+    assert tokens == [
+        "{", "a", ":", 1, "}", 0,
+        "{", "b", ":", 23, ",", "cä€", "}", 1,
+    ]
+
+
+def test_follow_split_utf8_char(growing_file):
+    path, append_later = growing_file
+    data = '["ä€"]'.encode("utf-8")
+    with open(path, "wb") as f:
+        f.write(data[:2])
+
+    def append():
+        for i in range(2, len(data)):
+            time.sleep(0.01)
+            with open(path, "ab") as f:
+                f.write(data[i : i + 1])
+
+    thread = threading.Thread(target=append)
+    thread.start()
+    with open(path, "rb") as f:
+        tokenizer = RustTokenizer(
+            f,
+            buffering=0,
+            follow=True,
+            poll_interval=0.002,
+            follow_timeout=0.5,
+        )
+        tokens = [val for kind, val in tokenizer]
+    thread.join()
+    assert tokens == ["[", "ä€", "]"]
+
+
+def test_follow_timeout(growing_file):
+    path, _ = growing_file
+    with open(path, "w") as f:
+        f.write("[1")
+    with open(path) as f:
+        tokenizer = RustTokenizer(
+            f, follow=True, poll_interval=0.01, follow_timeout=0.1
+        )
+        start = time.monotonic()
+        tokens = [val for kind, val in tokenizer]
+        elapsed = time.monotonic() - start
+    assert tokens == ["[", 1]
+    assert elapsed >= 0.1
+
+
+def test_follow_timeout_applies_to_each_pause(growing_file):
+    path, append_later = growing_file
+    with open(path, "w") as f:
+        f.write("[")
+    # each pause is shorter than the timeout, but all of them together aren't
+    thread = append_later(["1, ", "2, ", "3, ", "4, ", "5]"], delay=0.1)
+    with open(path) as f:
+        tokenizer = RustTokenizer(
+            f, follow=True, poll_interval=0.01, follow_timeout=0.3
+        )
+        tokens = [val for kind, val in tokenizer]
+    thread.join()
+    assert tokens == ["[", 1, ",", 2, ",", 3, ",", 4, ",", 5, "]"]
+
+
+def test_follow_timeout_excludes_time_outside_tokenizer(growing_file):
+    path, _ = growing_file
+    with open(path, "w") as f:
+        f.write("[1, 2")
+    with open(path) as f:
+        tokenizer = RustTokenizer(
+            f, follow=True, poll_interval=0.01, follow_timeout=0.1
+        )
+        assert next(tokenizer) == (0, "[")
+        time.sleep(0.2)
+        start = time.monotonic()
+        tokens = [val for kind, val in tokenizer]
+        elapsed = time.monotonic() - start
+    assert tokens == [1, ",", 2]
+    # waited for more data at the end despite the time spent sleeping
+    assert elapsed >= 0.1
+
+
+def test_follow_invalid_poll_interval():
+    with pytest.raises(ValueError):
+        RustTokenizer(open(os.devnull), follow=True, poll_interval=-1)
@@ -0,0 +1,158 @@
+"""
+Tests for using tokenizers from several threads at once.
+
+These are most meaningful on free-threaded Python builds, where the threads
+actually run in parallel, but also work on regular builds.
+"""
+import io
+import sys
+import sysconfig
+import threading
+from collections import Counter
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+N_THREADS = 8
+
+
+def make_doc(i):
+    return (
+        f'{{"thread": {i}, "items": ['
+        + ", ".join(f'{{"n": {j}, "s": "ä€{i}-{j}"}}' for j in range(500))
+        + f'], "long": "{"x" * 5000}"}}'
+    )
+
+
+def make_tokenizer(kind, doc):
+    if kind == "str":
+        return RustTokenizer(doc)
+    if kind == "bytes":
+        return RustTokenizer(doc.encode("utf-8"))
+    if kind == "StringIO":
+        return RustTokenizer(io.StringIO(doc), buffering=16)
+    if kind == "BytesIO":
+        return RustTokenizer(io.BytesIO(doc.encode("utf-8")))
+    if kind == "incremental":
+        tokenizer = RustTokenizer.incremental()
+        tokenizer.feed(doc)
+        tokenizer.close()
+        return tokenizer
+    raise ValueError(kind)
+
+
+def run_in_threads(target, n_threads=N_THREADS):
+    barrier = threading.Barrier(n_threads)
+    results = [None] * n_threads
+    errors = []
+
+    def run(i):
+        barrier.wait()
+        try:
+            results[i] = target(i)
+        except BaseException as e:
+            errors.append(e)
+
+    threads = [
+        threading.Thread(target=run, args=(i,)) for i in range(n_threads)
+    ]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    if errors:
+        raise errors[0]
+    return results
+
+
+@pytest.mark.skipif(
+    not sysconfig.get_config_var("Py_GIL_DISABLED"),
+    reason="only relevant for free-threaded builds",
+)
+def test_gil_stays_disabled():
+    assert not sys._is_gil_enabled()
+
+
+@pytest.mark.parametrize(
+    "kind", ["str", "bytes", "StringIO", "BytesIO", "incremental"]
+)
+def test_independent_tokenizers_in_parallel(kind):
+    docs = [make_doc(i) for i in range(N_THREADS)]
+    expected = [list(make_tokenizer(kind, doc)) for doc in docs]
+    results = run_in_threads(
+        lambda i: list(make_tokenizer(kind, docs[i]))
+    )
+    assert results == expected
+
+
+def test_independent_file_tokenizers_in_parallel(tmp_path):
+    docs = [make_doc(i) for i in range(N_THREADS)]
+    paths = []
+    for i, doc in enumerate(docs):
+        path = tmp_path / f"{i}.json"
+        path.write_text(doc, encoding="utf-8")
+        paths.append(path)
+    expected = [list(RustTokenizer(doc)) for doc in docs]
+
+    def tokenize(i):
+        with open(paths[i], "rb") as f:
+            return list(RustTokenizer(f, correct_cursor=True))
+
+    assert run_in_threads(tokenize) == expected
+
+
+def test_same_tokenizer_from_several_threads():
+    doc = make_doc(0)
+    expected = list(RustTokenizer(doc))
+    tokenizer = RustTokenizer(doc)
+
+    def take_tokens(i):
+        tokens = []
+        while True:
+            try:
+                tokens.append(next(tokenizer))
+            except StopIteration:
+                return tokens
+            except RuntimeError:
+                # another thread is using the tokenizer right now
+                continue
+
+    results = run_in_threads(take_tokens)
+    # each token is emitted exactly once, to whichever thread asked for it
+    assert Counter(repr(t) for r in results for t in r) == Counter(
+        repr(t) for t in expected
+    )
+
+
+def test_documents_while_tokenizer_in_use_by_other_thread():
+    reading = threading.Event()
+    release = threading.Event()
+
+    class BlockingReader:
+        def __init__(self):
+            self.data = io.StringIO('{"a": 1} {"b": 2}')
+
+        def read(self, size=-1):
+            if size != 0 and not release.is_set():
+                # keep the tokenizer busy in the other thread until released
+                reading.set()
+                release.wait()
+            return self.data.read(size)
+
+    tokenizer = RustTokenizer(BlockingReader())
+    documents = tokenizer.documents()
+    thread = threading.Thread(target=lambda: next(tokenizer))
+    thread.start()
+    try:
+        assert reading.wait(5)
+        with pytest.raises(RuntimeError):
+            next(documents)
+    finally:
+        release.set()
+        thread.join()
+    # the tokenizer is intact (the document that the other thread has started
+    # is skipped, as usual)
+    assert [[val for kind, val in doc] for doc in documents] == [
+        ["{", "b", ":", 2, "}"],
+    ]
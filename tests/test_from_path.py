@@ -0,0 +1,55 @@
+"""
+Tests for tokenizing memory-mapped files.
+"""
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer, TokenType
+
+from .conftest import write_file
+
+
+def test_from_path_remainder(file_path):
+    write_file(file_path, '{"ä": 1} {"b": 2}')
+    tokenizer = RustTokenizer.from_path(file_path)
+    tokens = [val for kind, val in islice(tokenizer, 5)]
+    assert tokens == ["{", "ä", ":", 1, "}"]
+    tokenizer.park_cursor()
+    assert tokenizer.remainder == b' {"b": 2}'
+
+
+def test_from_path_file_offset(file_path):
+    write_file(file_path, '{"ä": 1} {"b": 2}')
+    tokenizer = RustTokenizer.from_path(file_path)
+    assert tokenizer.file_offset == 0
+    tokens = [val for kind, val in islice(tokenizer, 5)]
+    assert tokens == ["{", "ä", ":", 1, "}"]
+    assert tokenizer.file_offset == 9
+    with open(file_path, "rb") as f:
+        f.seek(tokenizer.file_offset)
+        assert f.read() == tokenizer.remainder
+    assert list(tokenizer)
+    assert tokenizer.file_offset == 18
+
+
+def test_no_file_offset():
+    assert RustTokenizer("[]").file_offset is None
+
+
+def test_from_path_kwargs(file_path):
+    write_file(file_path, '{"a": 1}\n{"b": 2}\n')
+    tokenizer = RustTokenizer.from_path(file_path, ndjson=True)
+    assert [kind for kind, val in tokenizer].count(TokenType.RecordEnd) == 2
+
+
+def test_from_path_invalid_utf8(file_path):
+    with open(file_path, "wb") as f:
+        f.write(b'["\xff"]')
+    with pytest.raises(OSError, match="invalid UTF-8"):
+        list(RustTokenizer.from_path(file_path))
+
+
+def test_from_path_nonexistent(file_path):
+    with pytest.raises(FileNotFoundError):
+        RustTokenizer.from_path(file_path + "-nonexistent")
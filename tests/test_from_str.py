@@ -0,0 +1,47 @@
+"""
+Tests for tokenizing str objects directly.
+"""
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer, TokenType
+
+
+# one string for each of the internal storage kinds (Latin-1, UCS-2, UCS-4)
+@pytest.mark.parametrize("s", ["bä", "bä€", "bä€\U0001f600"])
+@pytest.mark.parametrize(
+    "make_tokenizer",
+    [RustTokenizer.from_str, RustTokenizer],
+    ids=["from_str", "constructor"],
+)
+def test_from_str(s, make_tokenizer):
+    tokenizer = make_tokenizer(f'{{"{s}": [1, 2.5, "{s}"], "b": null}}')
+    tokens = [val for kind, val in tokenizer]
+    assert tokens == [
+        "{", s, ":", "[", 1, ",", 2.5, ",", s, "]", ",", "b", ":", None, "}",
+    ]
+
+
+@pytest.mark.parametrize(
+    "s", ['[1] ["ä"]', '[1] ["€"]', '[1] ["\U0001f600"]']
+)
+def test_from_str_remainder(s):
+    tokenizer = RustTokenizer.from_str(s)
+    assert [val for kind, val in islice(tokenizer, 3)] == ["[", 1, "]"]
+    assert tokenizer.remainder == s[3:]
+
+
+def test_from_str_kwargs():
+    tokenizer = RustTokenizer.from_str('{"a": 1}\n{"b": 2}\n', ndjson=True)
+    assert [kind for kind, val in tokenizer].count(TokenType.RecordEnd) == 2
+
+
+def test_from_str_lone_surrogate():
+    with pytest.raises((OSError, UnicodeEncodeError)):
+        list(RustTokenizer.from_str('["\ud800"]'))
+
+
+def test_from_str_wrong_type():
+    with pytest.raises(TypeError):
+        RustTokenizer.from_str(b"[]")
@@ -0,0 +1,91 @@
+"""
+Tests for incremental tokenizers that are fed data via feed().
+"""
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer, TokenType
+
+
+@pytest.mark.parametrize("data_type", [str, bytes])
+def test_incremental(data_type):
+    def encode(s):
+        return s if data_type is str else s.encode("utf-8")
+
+    tokenizer = RustTokenizer.incremental()
+    assert list(tokenizer) == []
+    tokenizer.feed(encode('{"ab'))
+    assert list(tokenizer) == [(TokenType.Operator, "{")]
+    tokenizer.feed(encode('c": [1'))
+    assert list(tokenizer) == [
+        (TokenType.String_, "abc"),
+        (TokenType.Operator, ":"),
+        (TokenType.Operator, "["),
+    ]
+    tokenizer.feed(encode("23, tr"))
+    assert list(tokenizer) == [
+        (TokenType.Number, 123),
+        (TokenType.Operator, ","),
+    ]
+    tokenizer.feed(encode("ue]} 4"))
+    assert list(tokenizer) == [
+        (TokenType.Boolean, True),
+        (TokenType.Operator, "]"),
+        (TokenType.Operator, "}"),
+    ]
+    tokenizer.close()
+    assert list(tokenizer) == [(TokenType.Number, 4)]
+    assert list(tokenizer) == []
+
+
+def test_incremental_split_utf8_sequence():
+    tokenizer = RustTokenizer.incremental()
+    data = '["aä€😀"]'.encode("utf-8")
+    for i in range(len(data)):
+        tokenizer.feed(data[i : i + 1])
+    tokenizer.close()
+    assert [val for kind, val in tokenizer] == ["[", "aä€😀", "]"]
+
+
+def test_incremental_truncated_utf8_sequence():
+    tokenizer = RustTokenizer.incremental()
+    tokenizer.feed('"ä'.encode("utf-8")[:-1])
+    with pytest.raises(ValueError):
+        tokenizer.close()
+
+
+def test_incremental_feed_after_invalid_utf8():
+    tokenizer = RustTokenizer.incremental()
+    tokenizer.feed('["aä'.encode("utf-8")[:-1])
+    with pytest.raises(ValueError):
+        tokenizer.feed(b"\xff")
+    tokenizer.feed('ä"]'.encode("utf-8")[1:])
+    tokenizer.close()
+    assert [val for kind, val in tokenizer] == ["[", "aä", "]"]
+
+
+def test_incremental_invalid_input():
+    tokenizer = RustTokenizer.incremental()
+    with pytest.raises(TypeError):
+        tokenizer.feed(1)
+    tokenizer.feed("[")
+    with pytest.raises(ValueError):
+        tokenizer.feed(b"1")
+    tokenizer.close()
+    with pytest.raises(ValueError):
+        tokenizer.feed("1")
+
+
+def test_incremental_with_ndjson():
+    tokenizer = RustTokenizer.incremental(ndjson=True)
+    tokenizer.feed('{"a": 1}\n{"b"')
+    assert [val for kind, val in tokenizer] == [
+        "{", "a", ":", 1, "}", 0, "{", "b",
+    ]
+    tokenizer.feed(": 2}\n")
+    assert [val for kind, val in tokenizer] == [":", 2, "}", 1]
+
+
+def test_feed_requires_incremental_tokenizer(to_bytes_or_str_buf):
+    tokenizer = RustTokenizer(to_bytes_or_str_buf("1"))
+    with pytest.raises(ValueError):
+        tokenizer.feed("2")
@@ -0,0 +1,156 @@
+"""
+Tests for continuing to read after tokenizing via into_stream().
+"""
+import io
+import os
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+DOC = '{"ä": ["€\U0001f600", 1, 2.5]}'
+REST = ' {"b": 2} ' + "x" * 20000
+
+
+def tokenize_first_doc(tokenizer):
+    for kind, val in tokenizer:
+        if val == "}":
+            break
+
+
+@pytest.mark.parametrize("correct_cursor", [False, True])
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_into_stream(correct_cursor, buffering, to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(DOC + REST)
+    if correct_cursor and buffering > 1 and not buf.seekable():
+        pytest.skip("unsupported combination")
+    tokenizer = RustTokenizer(
+        buf, buffering=buffering, correct_cursor=correct_cursor
+    )
+    tokenize_first_doc(tokenizer)
+    rest = tokenizer.into_stream().read()
+    if isinstance(rest, bytes):
+        rest = rest.decode("utf-8")
+    assert rest == REST
+
+
+@pytest.mark.parametrize("size", [1, 3, 7, 1000])
+def test_into_stream_read_in_parts(size, to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(DOC + REST)
+    tokenizer = RustTokenizer(buf)
+    tokenize_first_doc(tokenizer)
+    stream = tokenizer.into_stream()
+    parts = []
+    while part := stream.read(size):
+        assert len(part) <= size
+        parts.append(part)
+    rest = parts[0][:0].join(parts)
+    if isinstance(rest, bytes):
+        rest = rest.decode("utf-8")
+    assert rest == REST
+
+
+@pytest.mark.parametrize("correct_cursor", [False, True])
+@pytest.mark.parametrize("buffering", [-1, 4])
+def test_into_stream_pipe(correct_cursor, buffering):
+    r, w = os.pipe()
+    os.write(w, (DOC + REST).encode("utf-8"))
+    os.close(w)
+    with open(r, "rb") as f:
+        tokenizer = RustTokenizer(
+            f, buffering=buffering, correct_cursor=correct_cursor
+        )
+        tokenize_first_doc(tokenizer)
+        assert tokenizer.into_stream().read() == REST.encode("utf-8")
+
+
+def test_into_stream_file(tmp_path):
+    path = tmp_path / "data.json"
+    path.write_text(DOC + REST, encoding="utf-8")
+    with open(path, "rb") as f:
+        tokenizer = RustTokenizer(f)
+        tokenize_first_doc(tokenizer)
+        assert tokenizer.into_stream().read() == REST.encode("utf-8")
+
+
+@pytest.mark.parametrize(
+    "source",
+    [
+        DOC + REST,
+        (DOC + REST).encode("utf-8"),
+        bytearray((DOC + REST).encode("utf-8")),
+    ],
+)
+def test_into_stream_in_memory(source):
+    tokenizer = RustTokenizer(source)
+    tokenize_first_doc(tokenizer)
+    rest = tokenizer.into_stream().read()
+    assert rest == (REST if isinstance(source, str) else REST.encode("utf-8"))
+
+
+@pytest.mark.parametrize("chunk_size", [1, 5, 100])
+@pytest.mark.parametrize("as_list", [False, True])
+def test_into_stream_chunks(chunk_size, as_list):
+    data = (DOC + REST).encode("utf-8")
+    chunks = (
+        data[i : i + chunk_size] for i in range(0, len(data), chunk_size)
+    )
+    if as_list:
+        chunks = list(chunks)
+    tokenizer = RustTokenizer(chunks)
+    tokenize_first_doc(tokenizer)
+    stream = tokenizer.into_stream()
+    assert stream.read(3) == REST.encode("utf-8")[:3]
+    assert stream.read() == REST.encode("utf-8")[3:]
+
+
+def test_into_stream_incremental():
+    tokenizer = RustTokenizer.incremental()
+    tokenizer.feed(DOC + ' {"b"')
+    tokenize_first_doc(tokenizer)
+    assert tokenizer.into_stream().read() == ' {"b"'
+    with pytest.raises(ValueError, match="consumed by into_stream"):
+        tokenizer.feed("more")
+
+
+def test_into_stream_tokenize_continuation(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(DOC + ' {"b": 2}')
+    tokenizer = RustTokenizer(buf)
+    tokenize_first_doc(tokenizer)
+    assert list(RustTokenizer(tokenizer.into_stream())) == [
+        (0, "{"),
+        (1, "b"),
+        (0, ":"),
+        (2, 2),
+        (0, "}"),
+    ]
+
+
+def test_into_stream_tokenizer_exhausted():
+    tokenizer = RustTokenizer(io.StringIO(DOC + ' {"b": 2}'))
+    tokenize_first_doc(tokenizer)
+    tokenizer.into_stream()
+    assert list(tokenizer) == []
+
+
+def test_into_stream_close():
+    source = io.BytesIO((DOC + REST).encode("utf-8"))
+    tokenizer = RustTokenizer(source)
+    tokenize_first_doc(tokenizer)
+    with tokenizer.into_stream() as stream:
+        assert stream.readable()
+        assert not stream.seekable()
+    assert stream.closed
+    assert not source.closed
+    with pytest.raises(ValueError):
+        stream.read()
+
+
+def test_into_stream_compression_unsupported():
+    import gzip
+
+    source = io.BytesIO(gzip.compress((DOC + REST).encode("utf-8")))
+    tokenizer = RustTokenizer(source, compression="gzip")
+    tokenize_first_doc(tokenizer)
+    with pytest.raises(ValueError, match="compressed"):
+        tokenizer.into_stream()
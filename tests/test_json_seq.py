@@ -0,0 +1,87 @@
+"""
+Tests for tokenizing RFC 7464 JSON text sequences (application/json-seq).
+"""
+import warnings
+
+import pytest
+
+from json_stream_rs_tokenizer import (
+    RustTokenizer,
+    TokenType,
+    TruncatedValueWarning,
+)
+
+RS = "\x1e"
+
+
+def test_json_seq_records(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(
+        f'{RS}{{"a": 1}}\n{RS}[2, "b"]\n{RS}{RS}3\n{RS}"c"{RS}true\n'
+    )
+    tokenizer = RustTokenizer(buf, json_seq=True)
+    assert [val for kind, val in tokenizer] == [
+        "{", "a", ":", 1, "}", 0,
+        "[", 2, ",", "b", "]", 1,
+        3, 2,
+        "c", 3,
+        True, 4,
+    ]
+
+
+def test_json_seq_record_end_tokens(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(f"{RS}1\n{RS}null\n")
+    tokens = list(RustTokenizer(buf, json_seq=True))
+    assert tokens == [
+        (TokenType.Number, 1),
+        (TokenType.RecordEnd, 0),
+        (TokenType.Null, None),
+        (TokenType.RecordEnd, 1),
+    ]
+
+
+def test_json_seq_truncated_top_level_values(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(f"{RS}12{RS}tru{RS}true{RS}-{RS}4\n{RS}5")
+    with pytest.warns(TruncatedValueWarning):
+        tokens = list(RustTokenizer(buf, json_seq=True))
+    assert tokens == [(TokenType.Number, 4), (TokenType.RecordEnd, 4)]
+
+
+def test_json_seq_truncated_value_warning_as_error(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(f"{RS}12{RS}[3]\n")
+    tokenizer = RustTokenizer(buf, json_seq=True)
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", TruncatedValueWarning)
+        with pytest.raises(TruncatedValueWarning):
+            next(tokenizer)
+        # tokenizing can continue after the exception
+        assert [val for kind, val in tokenizer] == ["[", 3, "]", 1]
+
+
+def test_json_seq_malformed_records(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(
+        f'{RS}{{"a": [1{RS}{RS}"b\n{RS}{{"c": 2}} 3\n{RS}{{"d": "e{RS}"f"'
+    )
+    tokens = list(RustTokenizer(buf, json_seq=True))
+    errors = [val for kind, val in tokens if kind == TokenType.Error_]
+    assert [error.record for error in errors] == [0, 1, 2, 3]
+    assert [error.message for error in errors] == [
+        "Unexpected record separator inside record",
+        "Unexpected record separator inside string",
+        "Expected record separator after record value.  Got '3'",
+        "Unexpected record separator inside string",
+    ]
+    assert tokens[-2:] == [(TokenType.String_, "f"), (TokenType.RecordEnd, 4)]
+
+
+def test_json_seq_documents(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(f'{RS}{{"a": 1}}\n{RS}false\n{RS}"b"\n')
+    tokenizer = RustTokenizer(buf, json_seq=True)
+    documents = [
+        [val for kind, val in document] for document in tokenizer.documents()
+    ]
+    assert documents == [["{", "a", ":", 1, "}", 0], [False, 1], ["b", 2]]
+
+
+def test_json_seq_and_ndjson_are_mutually_exclusive():
+    with pytest.raises(ValueError):
+        RustTokenizer("", ndjson=True, json_seq=True)
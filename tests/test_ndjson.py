@@ -0,0 +1,86 @@
+"""
+Tests for tokenizing newline-delimited JSON (JSON Lines).
+"""
+from json_stream_rs_tokenizer import Diagnostic, RustTokenizer, TokenType
+
+
+def test_ndjson_records(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": 1}\n[2, "b"]\n\n3\n"c"')
+    tokenizer = RustTokenizer(buf, ndjson=True)
+    assert [val for kind, val in tokenizer] == [
+        "{", "a", ":", 1, "}", 0,
+        "[", 2, ",", "b", "]", 1,
+        3, 3,
+        "c", 4,
+    ]
+
+
+def test_ndjson_record_end_tokens(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf("1\n2\n")
+    tokens = list(RustTokenizer(buf, ndjson=True))
+    assert tokens == [
+        (TokenType.Number, 1),
+        (TokenType.RecordEnd, 0),
+        (TokenType.Number, 2),
+        (TokenType.RecordEnd, 1),
+    ]
+
+
+def test_ndjson_malformed_records(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(
+        '{"a": 1}\n{"b": tru}\n{"c": "unterminated\n{"d": [4\n5 6\n{"e": 7}'
+    )
+    tokens = list(RustTokenizer(buf, ndjson=True))
+    errors = [val for kind, val in tokens if kind == TokenType.Error_]
+    assert all(isinstance(error, Diagnostic) for error in errors)
+    assert [error.record for error in errors] == [1, 2, 3, 4]
+    assert [error.message for error in errors] == [
+        "Invalid JSON character: '}'",
+        "Unexpected end of line inside string",
+        "Unexpected end of line inside record",
+        "Expected end of line after record value.  Got '6'",
+    ]
+    # tokens of malformed records preceding the error are still emitted
+    assert [val for kind, val in tokens if kind != TokenType.Error_] == [
+        "{", "a", ":", 1, "}", 0,
+        "{", "b", ":",
+        "{", "c", ":",
+        "{", "d", ":", "[", 4,
+        5,
+        "{", "e", ":", 7, "}", 5,
+    ]
+
+
+def test_ndjson_truncated_last_record(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": 1}\n{"b": [2')
+    tokens = list(RustTokenizer(buf, ndjson=True))
+    kind, error = tokens[-1]
+    assert kind == TokenType.Error_
+    assert error.message == "Unexpected end of file inside record"
+    assert error.record == 1
+
+
+def test_ndjson_documents(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": 1}\n\n"b"\n{"c": x}\n3\n')
+    tokenizer = RustTokenizer(buf, ndjson=True)
+    documents = [list(document) for document in tokenizer.documents()]
+    assert [[val for kind, val in document] for document in documents][
+        :2
+    ] == [["{", "a", ":", 1, "}", 0], ["b", 2]]
+    assert [kind for kind, val in documents[2]][-1] == TokenType.Error_
+    assert [val for kind, val in documents[3]] == [3, 4]
+    assert len(documents) == 4
+
+
+def test_ndjson_reset_after_complete_value(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": 1} {"b": 2}\n{"c": 3}\n')
+    tokenizer = RustTokenizer(buf, ndjson=True)
+    assert [next(tokenizer)[1] for _ in range(5)] == [
+        "{", "a", ":", 1, "}",
+    ]
+    tokenizer.reset()
+    # the record number is that of the line the tokens are on
+    assert [val for kind, val in tokenizer] == [
+        "{", "b", ":", 2, "}", 0,
+        "{", "c", ":", 3, "}", 1,
+    ]
@@ -0,0 +1,76 @@
+"""
+Tests for non-blocking streams that may not have data available yet.
+"""
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+class NonBlockingStream:
+    """
+    Unseekable stream that returns the given chunks, with None in place of
+    data signifying that no data is available yet (like non-blocking streams).
+    """
+
+    def __init__(self, chunks):
+        self.chunks = list(chunks)
+        self.empty = chunks[0][:0]
+
+    def read(self, n=-1):
+        if n == 0:
+            return self.empty
+        if not self.chunks:
+            return self.empty
+        chunk = self.chunks.pop(0)
+        if chunk is None:
+            return None
+        if n > 0 and len(chunk) > n:
+            self.chunks.insert(0, chunk[n:])
+            chunk = chunk[:n]
+        return chunk
+
+    def seekable(self):
+        return False
+
+
+def tokenize_with_retries(tokenizer):
+    """
+    Collect all tokens, recording BlockingIOErrors as None.
+    """
+    tokens = []
+    while True:
+        try:
+            token = next(tokenizer)
+        except BlockingIOError:
+            tokens.append(None)
+        except StopIteration:
+            return tokens
+        else:
+            tokens.append(token[1])
+
+
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+@pytest.mark.parametrize("data_type", [str, bytes])
+def test_non_blocking_stream(buffering, data_type):
+    def encode(s):
+        return s if data_type is str else s.encode("utf-8")
+
+    stream = NonBlockingStream(
+        [encode('{"a'), None, encode('bä": 1'), None, None, encode("2}")]
+    )
+    tokenizer = RustTokenizer(stream, buffering=buffering)
+    tokens = tokenize_with_retries(tokenizer)
+    assert [token for token in tokens if token is not None] == [
+        "{", "abä", ":", 12, "}",
+    ]
+    assert tokens.count(None) == 3
+
+
+def test_non_blocking_stream_split_utf8_char():
+    data = '["ä€"]'.encode("utf-8")
+    chunks = [b for i in range(len(data)) for b in (data[i : i + 1], None)]
+    tokenizer = RustTokenizer(NonBlockingStream(chunks), buffering=0)
+    tokens = tokenize_with_retries(tokenizer)
+    assert [token for token in tokens if token is not None] == [
+        "[", "ä€", "]",
+    ]
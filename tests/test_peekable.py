@@ -0,0 +1,97 @@
+"""
+Tests for correct cursors in unseekable streams that support peek().
+"""
+import io
+import os
+from itertools import islice
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+
+class UnseekableRaw(io.RawIOBase):
+    """
+    Unseekable raw stream over the given bytes (like a pipe or socket).
+    """
+
+    def __init__(self, data):
+        self.inner = io.BytesIO(data)
+
+    def readable(self):
+        return True
+
+    def readinto(self, b):
+        return self.inner.readinto(b)
+
+
+class CountingBufferedReader(io.BufferedReader):
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.n_calls = 0
+
+    def read(self, n=-1):
+        self.n_calls += 1
+        return super().read(n)
+
+    def read1(self, n=-1):
+        self.n_calls += 1
+        return super().read1(n)
+
+    def peek(self, n=0):
+        self.n_calls += 1
+        return super().peek(n)
+
+
+@pytest.mark.parametrize("buffer_size", [1, 2, 3, 4, 8192])
+@pytest.mark.parametrize("buffering", [-1, 4])
+def test_peekable_park_cursor(buffer_size, buffering):
+    data = '{"ä": "€\U0001f600"} {"b": 2}'.encode("utf-8")
+    f = io.BufferedReader(UnseekableRaw(data), buffer_size=buffer_size)
+    tokenizer = RustTokenizer(f, buffering=buffering, correct_cursor=True)
+    tokens = [val for kind, val in islice(tokenizer, 5)]
+    assert tokens == ["{", "ä", ":", "€\U0001f600", "}"]
+    tokenizer.park_cursor()
+    assert f.read() == b' {"b": 2}'
+
+
+def test_peekable_reads_in_chunks():
+    data = ("[" + ", ".join(["1"] * 1000) + "]").encode("utf-8")
+    f = CountingBufferedReader(UnseekableRaw(data), buffer_size=1024)
+    tokenizer = RustTokenizer(f, correct_cursor=True)
+    assert len(list(tokenizer)) == 2001
+    assert f.n_calls < 20
+
+
+def test_peekable_pipe():
+    r, w = os.pipe()
+    os.write(w, b'{"a": 1} {"b": 2}')
+    os.close(w)
+    with open(r, "rb") as rf:
+        tokenizer = RustTokenizer(rf, correct_cursor=True)
+        tokens = [val for kind, val in islice(tokenizer, 5)]
+        assert tokens == ["{", "a", ":", 1, "}"]
+        tokenizer.park_cursor()
+        assert rf.read() == b' {"b": 2}'
+
+
+def test_peekable_non_blocking_pipe():
+    r, w = os.pipe()
+    os.set_blocking(r, False)
+    os.write(w, '[1, "€'.encode("utf-8")[:-1])
+    with open(r, "rb") as rf:
+        tokenizer = RustTokenizer(rf, correct_cursor=True)
+        assert [val for kind, val in islice(tokenizer, 3)] == ["[", 1, ","]
+        with pytest.raises(BlockingIOError):
+            next(tokenizer)
+        os.write(w, '€"] {"b": 2}'.encode("utf-8")[2:])
+        os.close(w)
+        assert [val for kind, val in islice(tokenizer, 2)] == ["€", "]"]
+        tokenizer.park_cursor()
+        assert rf.read() == b' {"b": 2}'
+
+
+def test_peekable_invalid_utf8():
+    f = io.BufferedReader(UnseekableRaw(b'["\xe2\x82'), buffer_size=2)
+    with pytest.raises(OSError):
+        list(RustTokenizer(f, correct_cursor=True))
@@ -0,0 +1,117 @@
+"""
+Tests for reading ahead in a background thread via the prefetch option.
+"""
+import gzip
+import io
+import os
+import threading
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+from .conftest import write_file
+
+DOC = '{"ä": ["€\U0001f600", 1, 2.5], "b": "' + "あ" * 3000 + '"}'
+REST = ' {"c": 2} ' + "x" * 20000
+
+pytestmark = pytest.mark.skipif(
+    os.name != "posix", reason="prefetch is only supported on Unix"
+)
+
+
+def tokenize_first_doc(tokenizer):
+    for kind, val in tokenizer:
+        if val == "}" and not tokenizer.open_containers():
+            break
+
+
+@pytest.mark.parametrize("buffering", [-1, 5, "adaptive"])
+@pytest.mark.parametrize("correct_cursor", [False, True])
+def test_prefetch_same_tokens(file_path, buffering, correct_cursor):
+    write_file(file_path, DOC + " " + DOC)
+    tokens = []
+    for prefetch in [False, True]:
+        with open(file_path, "rb") as f:
+            tokenizer = RustTokenizer(
+                f,
+                buffering=buffering,
+                correct_cursor=correct_cursor,
+                prefetch=prefetch,
+            )
+            tokens.append(list(tokenizer))
+    assert tokens[0] == tokens[1]
+    assert len(tokens[1]) > 0
+
+
+@pytest.mark.parametrize("buffering", [-1, 5, 7, "adaptive"])
+def test_prefetch_park_cursor(file_path, buffering):
+    write_file(file_path, DOC + REST)
+    with open(file_path, "rb") as f:
+        tokenizer = RustTokenizer(
+            f, buffering=buffering, correct_cursor=True, prefetch=True
+        )
+        tokenize_first_doc(tokenizer)
+        tokenizer.park_cursor()
+        assert f.read() == REST.encode("utf-8")
+
+
+@pytest.mark.parametrize("buffering", [-1, 4])
+def test_prefetch_pipe_into_stream(buffering):
+    r, w = os.pipe()
+    data = (DOC + REST).encode("utf-8")
+    # (larger than the pipe's buffer, so writing has to happen in a thread)
+    def write():
+        with open(w, "wb") as wf:
+            wf.write(data)
+
+    writer = threading.Thread(target=write)
+    writer.start()
+    with open(r, "rb", buffering=0) as f:
+        tokenizer = RustTokenizer(f, buffering=buffering, prefetch=True)
+        tokenize_first_doc(tokenizer)
+        assert tokenizer.into_stream().read() == REST.encode("utf-8")
+    writer.join()
+
+
+def test_prefetch_ignored_for_pipes():
+    r, w = os.pipe()
+    with open(w, "wb", buffering=0) as wf, open(r, "rb", buffering=0) as f:
+        wf.write(b'{"a": [1, 2]} {"c"')
+        tokenizer = RustTokenizer(f, prefetch=True)
+        remainders = []
+
+        def tokenize():
+            tokenize_first_doc(tokenizer)
+            remainders.append(tokenizer.remainder)
+
+        # the writer is still open, so waiting for data read ahead would block
+        thread = threading.Thread(target=tokenize, daemon=True)
+        thread.start()
+        thread.join(5)
+        assert remainders == [b' {"c"']
+
+
+def test_prefetch_file_position(file_path):
+    write_file(file_path, DOC + REST)
+    with open(file_path, "rb") as f:
+        tokenizer = RustTokenizer(f, buffering=100, prefetch=True)
+        assert next(tokenizer) == (0, "{")
+        # only the chunk being tokenized counts as read
+        assert f.tell() == 100
+
+
+def test_prefetch_compression_unsupported(file_path):
+    with open(file_path, "wb") as f:
+        f.write(gzip.compress(DOC.encode("utf-8")))
+    with open(file_path, "rb") as f:
+        with pytest.raises(ValueError, match="prefetch"):
+            RustTokenizer(f, compression="gzip", prefetch=True)
+
+
+def test_prefetch_ignored_for_other_streams():
+    buf = io.BytesIO((DOC + REST).encode("utf-8"))
+    tokenizer = RustTokenizer(buf, correct_cursor=True, prefetch=True)
+    tokenize_first_doc(tokenizer)
+    tokenizer.park_cursor()
+    assert buf.read() == REST.encode("utf-8")
@@ -0,0 +1,85 @@
+"""
+Tests for reading from bytes streams via readinto().
+"""
+import io
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+from .conftest import DATA, TOKENS
+
+
+class CountingBytesIO(io.BytesIO):
+    """
+    BytesIO that counts calls of read() and readinto().
+    """
+
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.n_reads = 0
+        self.n_readintos = 0
+
+    def read(self, n=-1):
+        self.n_reads += 1
+        return super().read(n)
+
+    def readinto(self, b):
+        self.n_readintos += 1
+        return super().readinto(b)
+
+
+class UnsupportedReadintoBytesIO(CountingBytesIO):
+    def readinto(self, b):
+        self.n_readintos += 1
+        raise io.UnsupportedOperation("readinto")
+
+
+class ReadOnlyStream:
+    """
+    Bytes stream without a readinto() method.
+    """
+
+    def __init__(self, data):
+        self.inner = io.BytesIO(data)
+
+    def read(self, n=-1):
+        return self.inner.read(n)
+
+    def seekable(self):
+        return False
+
+
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_readinto(buffering):
+    stream = CountingBytesIO(DATA.encode("utf-8"))
+    tokens = [val for kind, val in RustTokenizer(stream, buffering=buffering)]
+    assert tokens == TOKENS
+    assert stream.n_readintos > 0
+    # only used once to determine the stream's data type
+    assert stream.n_reads == 1
+
+
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_readinto_unsupported_falls_back_to_read(buffering):
+    stream = UnsupportedReadintoBytesIO(DATA.encode("utf-8"))
+    tokens = [val for kind, val in RustTokenizer(stream, buffering=buffering)]
+    assert tokens == TOKENS
+    assert stream.n_readintos == 1
+    assert stream.n_reads > 1
+
+
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_no_readinto(buffering):
+    stream = ReadOnlyStream(DATA.encode("utf-8"))
+    tokens = [val for kind, val in RustTokenizer(stream, buffering=buffering)]
+    assert tokens == TOKENS
+
+
+def test_readinto_broken_stream():
+    class BrokenBytesIO(io.BytesIO):
+        def readinto(self, b):
+            return len(b) + 1
+
+    with pytest.raises(OSError, match="more bytes than requested"):
+        list(RustTokenizer(BrokenBytesIO(b"[1, 2]")))
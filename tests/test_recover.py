@@ -0,0 +1,64 @@
+"""
+Tests for recovering from JSON syntax errors.
+"""
+import pytest
+
+from json_stream_rs_tokenizer import Diagnostic, RustTokenizer, TokenType
+
+
+def test_recover_collects_errors(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"a": tru, "b": [1x, 2], "c": "d\\q"}')
+    tokenizer = RustTokenizer(buf, recover=True)
+    assert [val for kind, val in tokenizer] == [
+        "{", "a", ":", ",", "b", ":", "[", ",", 2, "]", ",", "c", ":", "}",
+    ]
+    errors = tokenizer.errors
+    assert all(isinstance(error, Diagnostic) for error in errors)
+    assert [(error.message, error.index) for error in errors] == [
+        ("Invalid JSON character: ','", 9),
+        ("A number must contain only digits.  Got 'x'", 18),
+        ("Invalid string escape: q", 33),
+    ]
+    assert all(error.record is None for error in errors)
+
+
+def test_recover_emit_error_tokens(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf("[1, nul, 3]")
+    tokenizer = RustTokenizer(buf, recover=True, emit_error_tokens=True)
+    tokens = list(tokenizer)
+    assert [kind for kind, val in tokens] == [
+        TokenType.Operator,
+        TokenType.Number,
+        TokenType.Operator,
+        TokenType.Error_,
+        TokenType.Operator,
+        TokenType.Number,
+        TokenType.Operator,
+    ]
+    assert tokens[3][1] is tokenizer.errors[0]
+
+
+def test_recover_at_end_of_file(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('["a", "unterminated')
+    tokenizer = RustTokenizer(buf, recover=True)
+    assert [val for kind, val in tokenizer] == ["[", "a", ","]
+    assert [error.message for error in tokenizer.errors] == [
+        "Unterminated string at end of file"
+    ]
+
+
+def test_recover_with_ndjson(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf("1\nx\n3\n")
+    tokenizer = RustTokenizer(buf, ndjson=True, recover=True)
+    tokens = list(tokenizer)
+    errors = [val for kind, val in tokens if kind == TokenType.Error_]
+    assert errors == tokenizer.errors
+    assert [error.record for error in errors] == [1]
+
+
+def test_no_recover_raises(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf("[1, nul, 3]")
+    tokenizer = RustTokenizer(buf)
+    with pytest.raises(ValueError):
+        list(tokenizer)
+    assert tokenizer.errors == []
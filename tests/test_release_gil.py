@@ -0,0 +1,62 @@
+"""
+Tests for releasing the GIL while scanning long tokens.
+"""
+import threading
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+LONG_STRING = "あ" * 5_000_000
+DOC = f'["{LONG_STRING}"]'
+
+
+def make_source(kind, tmp_path):
+    if kind == "str":
+        return DOC
+    if kind == "bytes":
+        return DOC.encode("utf-8")
+    path = tmp_path / "data.json"
+    path.write_text(DOC, encoding="utf-8")
+    if kind == "path":
+        return path
+    return open(path, "rb")
+
+
+@pytest.mark.parametrize("kind", ["str", "bytes", "file", "path"])
+def test_other_threads_run_during_long_token(kind, tmp_path):
+    source = make_source(kind, tmp_path)
+    if kind == "path":
+        tokenizer = RustTokenizer.from_path(source)
+    else:
+        tokenizer = RustTokenizer(source)
+    assert next(tokenizer) == (0, "[")
+    started = threading.Event()
+    ran_during_next = threading.Event()
+    stop = threading.Event()
+
+    def other():
+        # accessing the tokenizer only fails while another thread is inside
+        # __next__, which this thread can observe only if the GIL is released
+        # there (or if there is no GIL at all)
+        started.set()
+        while not stop.is_set():
+            try:
+                tokenizer.partial
+            except RuntimeError:
+                ran_during_next.set()
+                return
+
+    thread = threading.Thread(target=other)
+    thread.start()
+    started.wait()
+    try:
+        token = next(tokenizer)
+    finally:
+        stop.set()
+        thread.join()
+    assert token == (1, LONG_STRING)
+    assert ran_during_next.is_set()
+    assert list(tokenizer) == [(0, "]")]
+    if kind == "file":
+        source.close()
@@ -0,0 +1,132 @@
+"""
+Tests for extracting JSON values embedded in arbitrary text.
+"""
+import pytest
+
+from json_stream_rs_tokenizer import Diagnostic, RustTokenizer, TokenType
+
+
+def test_scan_log_lines(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(
+        '2024-01-01 12:00 INFO got {"a": 1}\n'
+        "2024-01-01 12:01 DEBUG list: [2, true] (done)\n"
+    )
+    tokenizer = RustTokenizer(buf, scan=True)
+    assert list(tokenizer) == [
+        (TokenType.ValueStart, 26),
+        (TokenType.Operator, "{"),
+        (TokenType.String_, "a"),
+        (TokenType.Operator, ":"),
+        (TokenType.Number, 1),
+        (TokenType.Operator, "}"),
+        (TokenType.ValueStart, 64),
+        (TokenType.Operator, "["),
+        (TokenType.Number, 2),
+        (TokenType.Operator, ","),
+        (TokenType.Boolean, True),
+        (TokenType.Operator, "]"),
+    ]
+
+
+def test_scan_markdown_code_fence(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf(
+        'Sure! Here is the JSON:\n```json\n{"b": [null]}\n```\nAnything else?'
+    )
+    tokenizer = RustTokenizer(buf, scan=True)
+    assert [val for kind, val in tokenizer] == [
+        32, "{", "b", ":", "[", None, "]", "}",
+    ]
+
+
+def test_scan_backtracks_after_failed_candidate(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('[see {"c": 3}] and [x]')
+    tokens = list(RustTokenizer(buf, scan=True))
+    assert tokens[:2] == [(TokenType.ValueStart, 0), (TokenType.Operator, "[")]
+    kind, error = tokens[2]
+    assert kind == TokenType.Error_
+    assert isinstance(error, Diagnostic)
+    assert error.index == 1
+    assert tokens[3:] == [
+        (TokenType.ValueStart, 5),
+        (TokenType.Operator, "{"),
+        (TokenType.String_, "c"),
+        (TokenType.Operator, ":"),
+        (TokenType.Number, 3),
+        (TokenType.Operator, "}"),
+        (TokenType.ValueStart, 19),
+        (TokenType.Operator, "["),
+        (TokenType.Error_, tokens[-1][1]),
+    ]
+    assert tokens[-1][1].index == 20
+
+
+def test_scan_unterminated_candidate(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"d": [4, {"e": 5}')
+    tokens = list(RustTokenizer(buf, scan=True))
+    errors = [val for kind, val in tokens if kind == TokenType.Error_]
+    assert [error.message for error in errors] == [
+        "Unexpected end of file inside value",
+        "Unexpected end of file inside value",
+    ]
+    assert [val for kind, val in tokens if kind == TokenType.ValueStart] == [
+        0, 6, 10,
+    ]
+    assert tokens[-5:] == [
+        (TokenType.Operator, "{"),
+        (TokenType.String_, "e"),
+        (TokenType.Operator, ":"),
+        (TokenType.Number, 5),
+        (TokenType.Operator, "}"),
+    ]
+
+
+def test_scan_documents(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('a {"f": 6} b [7 c [8] d')
+    tokenizer = RustTokenizer(buf, scan=True)
+    documents = [
+        [val for kind, val in document] for document in tokenizer.documents()
+    ]
+    assert documents[0] == [2, "{", "f", ":", 6, "}"]
+    assert documents[1][:3] == [13, "[", 7]
+    assert documents[2] == [18, "[", 8, "]"]
+    assert len(documents) == 3
+
+
+def consume_scanned_value(tokenizer):
+    """
+    Consume tokens up to the end of the first valid candidate value.
+    """
+    for kind, val in tokenizer:
+        if kind == TokenType.Operator and not tokenizer.open_containers():
+            return
+
+
+@pytest.mark.parametrize(
+    "source", ['{"k": [1, 2] x} tail', b'{"k": [1, 2] x} tail']
+)
+def test_scan_remainder_includes_replayed_chars(source):
+    tokenizer = RustTokenizer(source, scan=True)
+    consume_scanned_value(tokenizer)
+    # " x" was read for the failed candidate but is still to be replayed
+    assert tokenizer.remainder == source[-8:]
+
+
+def test_scan_park_cursor_before_replayed_chars(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"k": [1, 2] x} tail')
+    tokenizer = RustTokenizer(buf, scan=True)
+    consume_scanned_value(tokenizer)
+    tokenizer.park_cursor()
+    rest = tokenizer.remainder + buf.read()
+    assert rest in (" x} tail", b" x} tail")
+
+
+def test_scan_into_stream_includes_replayed_chars(to_bytes_or_str_buf):
+    buf = to_bytes_or_str_buf('{"k": [1, 2] x} tail')
+    tokenizer = RustTokenizer(buf, scan=True)
+    consume_scanned_value(tokenizer)
+    assert tokenizer.into_stream().read() in (" x} tail", b" x} tail")
+
+
+def test_scan_and_ndjson_are_mutually_exclusive():
+    with pytest.raises(ValueError):
+        RustTokenizer("", ndjson=True, scan=True)
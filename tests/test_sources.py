@@ -0,0 +1,12 @@
+"""
+Tests for tokenizing the same data from all kinds of sources.
+"""
+from .conftest import DATA, TOKENS
+
+
+def test_source(tokenize_from_source):
+    assert tokenize_from_source(DATA) == TOKENS
+
+
+def test_source_empty(tokenize_from_source):
+    assert tokenize_from_source("") == []
@@ -0,0 +1,117 @@
+"""
+Tests for file-like objects with few methods and for the mode and seekable
+options that skip probing the stream.
+"""
+import io
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+JSON = '{"a": ["ä", 1]} {"b": 2}'
+TOKENS = [(0, "{"), (1, "a"), (0, ":"), (0, "["), (1, "ä"), (0, ",")]
+
+
+class ReadOnly:
+    """
+    Minimal file-like object that only has a read() method.
+    """
+
+    def __init__(self, data):
+        if isinstance(data, bytes):
+            self.inner = io.BytesIO(data)
+        else:
+            self.inner = io.StringIO(data)
+
+    def read(self, size=-1):
+        return self.inner.read(size)
+
+
+class NoEmptyReads(ReadOnly):
+    """
+    File-like object that doesn't allow probing via read(0).
+    """
+
+    def read(self, size=-1):
+        if size == 0:
+            raise AssertionError("read(0) called")
+        return super().read(size)
+
+
+class RecordingBytesIO(io.BytesIO):
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.calls = []
+
+    def seekable(self):
+        self.calls.append("seekable")
+        return super().seekable()
+
+    def seek(self, *args):
+        self.calls.append("seek")
+        return super().seek(*args)
+
+
+@pytest.mark.parametrize("as_bytes", [False, True])
+@pytest.mark.parametrize("buffering", [-1, 0, 4])
+def test_read_only(as_bytes, buffering):
+    data = JSON.encode("utf-8") if as_bytes else JSON
+    tokenizer = RustTokenizer(ReadOnly(data), buffering=buffering)
+    assert list(tokenizer)[:6] == TOKENS
+
+
+@pytest.mark.parametrize("as_bytes", [False, True])
+def test_read_only_correct_cursor(as_bytes):
+    data = JSON.encode("utf-8") if as_bytes else JSON
+    stream = ReadOnly(data)
+    tokenizer = RustTokenizer(stream, correct_cursor=True)
+    for kind, val in tokenizer:
+        if val == "}":
+            break
+    rest = ' {"b": 2}'
+    assert stream.read() == (rest.encode("utf-8") if as_bytes else rest)
+
+
+@pytest.mark.parametrize("mode,as_bytes", [("text", False), ("bytes", True)])
+def test_mode(mode, as_bytes):
+    data = JSON.encode("utf-8") if as_bytes else JSON
+    tokenizer = RustTokenizer(NoEmptyReads(data), mode=mode)
+    assert list(tokenizer)[:6] == TOKENS
+
+
+def test_without_mode_probes():
+    with pytest.raises(AssertionError, match="read"):
+        RustTokenizer(NoEmptyReads(JSON))
+
+
+def test_seekable_override():
+    stream = RecordingBytesIO(JSON.encode("utf-8"))
+    tokenizer = RustTokenizer(stream, correct_cursor=True, seekable=False)
+    for kind, val in tokenizer:
+        if val == "}":
+            break
+    tokenizer.park_cursor()
+    assert stream.read() == b' {"b": 2}'
+    assert stream.calls == []
+
+
+def test_seekable_without_seek_method():
+    with pytest.raises(TypeError, match=r"seek\(\)"):
+        RustTokenizer(ReadOnly(JSON), seekable=True)
+
+
+def test_invalid_mode():
+    with pytest.raises(ValueError, match="mode"):
+        RustTokenizer(io.StringIO(JSON), mode="binary")
+
+
+@pytest.mark.parametrize("option", ["mode", "seekable"])
+def test_options_unsupported_for_non_file_like(option):
+    value = "text" if option == "mode" else True
+    with pytest.raises(ValueError, match=option):
+        RustTokenizer(JSON, **{option: value})
+
+
+def test_non_file_like_error_lists_requirements():
+    with pytest.raises(TypeError, match=r"read\(\)"):
+        RustTokenizer(1)
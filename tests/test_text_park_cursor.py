@@ -0,0 +1,102 @@
+"""
+Tests for parking the cursor in seekable text streams.
+"""
+import io
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer
+
+REST = ' {"b": "ソ"}'
+
+
+def make_doc(encoding):
+    # Shift-JIS has neither umlauts nor emoji
+    special = "Ω×" if encoding == "shift_jis" else "ä€\U0001f600"
+    long_value = "あ" * 3000
+    return f'{{"キー": ["値", "{special}", 1, 2.5], "b": "{long_value}"}}'
+
+
+class RecordingTextIOWrapper(io.TextIOWrapper):
+    """
+    Text wrapper that records the sizes of reads after the last seek.
+    """
+
+    def __init__(self, *args, **kwargs):
+        super().__init__(*args, **kwargs)
+        self.reads_since_seek = []
+
+    def seek(self, *args):
+        self.reads_since_seek = []
+        return super().seek(*args)
+
+    def read(self, size=-1):
+        self.reads_since_seek.append(size)
+        return super().read(size)
+
+
+def tokenize_first_doc(tokenizer):
+    for kind, val in tokenizer:
+        if val == "}" and not tokenizer.open_containers():
+            break
+
+
+@pytest.mark.parametrize("encoding", ["utf-8", "utf-16", "shift_jis"])
+@pytest.mark.parametrize("buffering", [-1, 2, 5, 16, 7000])
+def test_text_park_cursor(encoding, buffering):
+    doc = make_doc(encoding)
+    f = io.TextIOWrapper(
+        io.BytesIO((doc + REST).encode(encoding)), encoding=encoding
+    )
+    tokenizer = RustTokenizer(f, buffering=buffering, correct_cursor=True)
+    tokenize_first_doc(tokenizer)
+    tokenizer.park_cursor()
+    assert f.read() == REST
+
+
+@pytest.mark.parametrize("encoding", ["utf-8", "utf-16", "shift_jis"])
+def test_text_park_cursor_twice_and_continue(encoding):
+    doc = make_doc(encoding)
+    f = io.TextIOWrapper(
+        io.BytesIO((doc + REST).encode(encoding)), encoding=encoding
+    )
+    tokenizer = RustTokenizer(f, correct_cursor=True)
+    tokenize_first_doc(tokenizer)
+    tokenizer.park_cursor()
+    tokenizer.park_cursor()
+    assert list(tokenizer) == [
+        (0, "{"),
+        (1, "b"),
+        (0, ":"),
+        (1, "ソ"),
+        (0, "}"),
+    ]
+    tokenizer.park_cursor()
+    assert f.read() == ""
+
+
+@pytest.mark.parametrize("encoding", ["utf-8", "utf-16", "shift_jis"])
+@pytest.mark.parametrize("buffering", [-1, 3, "doc-length"])
+def test_text_park_cursor_at_eof(encoding, buffering):
+    doc = make_doc(encoding)
+    if buffering == "doc-length":
+        buffering = len(doc)
+    data = doc.encode(encoding)
+    f = io.TextIOWrapper(io.BytesIO(data), encoding=encoding)
+    tokenizer = RustTokenizer(f, buffering=buffering, correct_cursor=True)
+    assert list(tokenizer)[-1] == (0, "}")
+    tokenizer.park_cursor()
+    assert f.read() == ""
+    assert f.buffer.tell() == len(data)
+
+
+def test_text_park_cursor_rereads_only_processed_chars():
+    doc = make_doc("utf-16")
+    f = RecordingTextIOWrapper(
+        io.BytesIO((doc + REST * 1000).encode("utf-16")), encoding="utf-16"
+    )
+    tokenizer = RustTokenizer(f, buffering=8000, correct_cursor=True)
+    tokenize_first_doc(tokenizer)
+    tokenizer.park_cursor()
+    assert f.reads_since_seek == [len(doc)]
+    assert f.read() == REST * 1000
@@ -0,0 +1,54 @@
+"""
+Tests for the options shared by all ways of creating a tokenizer.
+"""
+import io
+
+import pytest
+
+from json_stream_rs_tokenizer import RustTokenizer, TokenType
+
+from .conftest import write_file
+
+DOC = '{"a": 1}\n{"b": 2}\n'
+
+
+@pytest.fixture(params=["new", "incremental", "from_str", "from_path"])
+def create(request, file_path):
+    def create(**options):
+        if request.param == "new":
+            return RustTokenizer(io.StringIO(DOC), **options)
+        if request.param == "incremental":
+            tokenizer = RustTokenizer.incremental(**options)
+            tokenizer.feed(DOC)
+            tokenizer.close()
+            return tokenizer
+        if request.param == "from_str":
+            return RustTokenizer.from_str(DOC, **options)
+        write_file(file_path, DOC)
+        return RustTokenizer.from_path(file_path, **options)
+
+    return create
+
+
+def test_options_applied(create):
+    tokenizer = create(ndjson=True)
+    assert [kind for kind, val in tokenizer].count(TokenType.RecordEnd) == 2
+
+
+def test_unknown_option(create):
+    with pytest.raises(TypeError, match="unexpected keyword argument 'x'"):
+        create(x=True)
+
+
+def test_option_wrong_type(create):
+    with pytest.raises(TypeError, match="argument 'recover'"):
+        create(recover="yes")
+
+
+@pytest.mark.parametrize(
+    "options",
+    [dict(ndjson=True, json_seq=True), dict(ndjson=True, scan=True)],
+)
+def test_conflicting_options(create, options):
+    with pytest.raises(ValueError, match="mutually exclusive"):
+        create(**options)